@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+use crate::{
+    camera::SPRITE_SIZE,
+    items::{
+        packets::{PacketStats, PacketType},
+        projectiles::{ProjectileBehaviour, ProjectileStats, ProjectileType},
+        registry::ItemRegistry,
+    },
+};
+
+pub const BALANCE_PATH: &str = "balance.json";
+
+// numbers from assets/balance.json, anything it leaves out keeps the value compiled into the game
+#[derive(serde::Deserialize, Asset, Resource, TypePath, Default, Clone)]
+#[serde(default)]
+pub struct Balance {
+    pub packets: HashMap<PacketType, PacketBalance>,
+    pub projectiles: HashMap<ProjectileType, ProjectileBalance>,
+    // shop prices by item key
+    pub prices: HashMap<String, u32>,
+    // seconds between packets of a server that wasn't upgraded
+    pub fire_rate: Option<f32>,
+    // upgrade tables by item key
+    pub upgrades: HashMap<String, UpgradeTable>,
+}
+
+#[derive(serde::Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct PacketBalance {
+    pub speed: Option<f32>,
+    pub health: Option<i32>,
+    pub damage: Option<i32>,
+    pub shot_multi: Option<i32>,
+}
+
+// distances are in cells
+#[derive(serde::Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ProjectileBalance {
+    pub speed: Option<f32>,
+    pub damage: Option<i32>,
+    pub radius: Option<f32>,
+    pub jumps: Option<u32>,
+    pub range: Option<f32>,
+    pub falloff: Option<f32>,
+    pub max_distance: Option<f32>,
+}
+
+#[derive(serde::Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct UpgradeTable {
+    pub init_price: Option<i32>,
+    pub levels: Vec<UpgradeLevelBalance>,
+}
+
+// what upgrading to a level does, the values are named by each item's upgrades
+#[derive(serde::Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct UpgradeLevelBalance {
+    pub next_price: Option<i32>,
+    #[serde(flatten)]
+    pub values: HashMap<String, f32>,
+}
+
+impl Balance {
+    pub fn packet_stats(&self, packet_type: PacketType) -> PacketStats {
+        let mut stats: PacketStats = packet_type.into();
+        let Some(o) = self.packets.get(&packet_type) else {
+            return stats;
+        };
+
+        stats.speed = o.speed.unwrap_or(stats.speed);
+        stats.health = o.health.unwrap_or(stats.health);
+        stats.damage = o.damage.unwrap_or(stats.damage);
+        stats.shot_multi = o.shot_multi.unwrap_or(stats.shot_multi);
+        stats
+    }
+
+    pub fn projectile_stats(&self, projectile_type: ProjectileType) -> ProjectileStats {
+        let mut stats: ProjectileStats = projectile_type.into();
+        let Some(o) = self.projectiles.get(&projectile_type) else {
+            return stats;
+        };
+
+        let cells = |value: Option<f32>, default: f32| value.map_or(default, |v| v * SPRITE_SIZE);
+
+        stats.speed = o.speed.unwrap_or(stats.speed);
+        stats.damage = o.damage.unwrap_or(stats.damage);
+        stats.behaviour = match stats.behaviour {
+            ProjectileBehaviour::Single => ProjectileBehaviour::Single,
+            ProjectileBehaviour::Splash { radius } => ProjectileBehaviour::Splash {
+                radius: cells(o.radius, radius),
+            },
+            ProjectileBehaviour::Chain {
+                jumps,
+                range,
+                falloff,
+            } => ProjectileBehaviour::Chain {
+                jumps: o.jumps.unwrap_or(jumps),
+                range: cells(o.range, range),
+                falloff: o.falloff.unwrap_or(falloff),
+            },
+            ProjectileBehaviour::Piercing { max_distance } => ProjectileBehaviour::Piercing {
+                max_distance: cells(o.max_distance, max_distance),
+            },
+        };
+        stats
+    }
+
+    pub fn upgrades(&self, key: &str) -> &UpgradeTable {
+        static EMPTY: UpgradeTable = UpgradeTable {
+            init_price: None,
+            levels: Vec::new(),
+        };
+        self.upgrades.get(key).unwrap_or(&EMPTY)
+    }
+}
+
+impl UpgradeTable {
+    pub fn init_price(&self, default: i32) -> i32 {
+        self.init_price.unwrap_or(default)
+    }
+
+    // cost of the upgrade after `level`, the table can't add levels the item doesn't have
+    pub fn next_price(&self, level: u32, default: Option<i32>) -> Option<i32> {
+        let price = self.levels.get(level as usize).and_then(|l| l.next_price);
+        default.map(|p| price.unwrap_or(p))
+    }
+
+    pub fn value(&self, level: u32, name: &str, default: f32) -> f32 {
+        self.levels
+            .get(level as usize)
+            .and_then(|l| l.values.get(name))
+            .copied()
+            .unwrap_or(default)
+    }
+}
+
+#[derive(Resource)]
+struct BalanceHandle(Handle<Balance>);
+
+pub struct BalancePlugin;
+
+impl Plugin for BalancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Balance>();
+        app.add_plugins(JsonAssetPlugin::<Balance>::new(&["balance.json"]));
+        app.add_systems(Startup, load_balance);
+        app.add_systems(Update, apply_balance);
+    }
+}
+
+fn load_balance(asset_server: Res<AssetServer>, mut commands: Commands) {
+    commands.insert_resource(BalanceHandle(asset_server.load(BALANCE_PATH)));
+}
+
+//swaps in the balance file every time it's (re)loaded
+fn apply_balance(
+    mut events: EventReader<AssetEvent<Balance>>,
+    handle: Res<BalanceHandle>,
+    balances: Res<Assets<Balance>>,
+    mut balance: ResMut<Balance>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        if id != handle.0.id() {
+            continue;
+        }
+        let Some(new_balance) = balances.get(id) else {
+            continue;
+        };
+
+        info!("balance loaded");
+        *balance = new_balance.clone();
+        registry.apply_prices(&balance.prices);
+    }
+}
@@ -1,7 +1,6 @@
 use bevy::{math::vec3, prelude::*};
 
-use crate::grid::GRID_M;
-use crate::grid::GRID_N;
+use crate::grid::{DEFAULT_GRID_SIZE, GridRoot};
 
 pub const SCALE: f32 = 0.5;
 pub const SPRITE_SIZE: f32 = 21.;
@@ -17,7 +16,7 @@ impl Plugin for CameraPlugin {
         app.init_resource::<Screen>();
 
         app.add_systems(Startup, init_camera);
-        app.add_systems(Update, update_screen);
+        app.add_systems(Update, (frame_grid, update_screen).chain());
     }
 }
 
@@ -29,14 +28,36 @@ pub fn init_camera(mut commands: Commands) {
             ..OrthographicProjection::default_2d()
         },
         Msaa::Off,
-        Transform::from_translation(vec3(
-            GRID_N as f32 / 2. * SPRITE_SIZE - SPRITE_SIZE,
-            GRID_M as f32 / 2. * SPRITE_SIZE - 34., // subtracted value is arbitrary so it looks good
-            10.,
-        )),
+        Transform::from_translation(grid_center(DEFAULT_GRID_SIZE)),
     ));
 }
 
+// where the camera looks at a grid of this size from
+pub fn grid_center(size: UVec2) -> Vec3 {
+    vec3(
+        size.x as f32 / 2. * SPRITE_SIZE - SPRITE_SIZE,
+        size.y as f32 / 2. * SPRITE_SIZE - 34., // subtracted value is arbitrary so it looks good
+        10.,
+    )
+}
+
+//centers the camera on a newly loaded grid, zooming out if it's bigger than the default one
+fn frame_grid(
+    roots: Query<&GridRoot, Added<GridRoot>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let Some(root) = roots.iter().last() else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    let ratio = root.size.as_vec2() / DEFAULT_GRID_SIZE.as_vec2();
+    projection.scale = SCALE * ratio.max_element().max(1.);
+    transform.translation = grid_center(root.size);
+}
+
 pub fn update_screen(
     mut screen: ResMut<Screen>,
     windows: Query<&Window>,
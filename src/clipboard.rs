@@ -0,0 +1,15 @@
+use arboard::Clipboard;
+
+//puts the text on the system clipboard
+pub fn copy(text: &str) -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| e.to_string())
+}
+
+//the text on the system clipboard
+pub fn paste() -> Result<String, String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| e.to_string())
+}
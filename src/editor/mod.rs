@@ -0,0 +1,383 @@
+use std::fs;
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use ui::{EditorText, EditorUIPlugin, EraserBtnPress, ExportBtnPress, FieldBtnPress, SaveBtnPress};
+
+use crate::{
+    clipboard,
+    game::{EditorStates, GameStates},
+    grid::{
+        Grid,
+        format::{Difficulty, today},
+        level_code::export_code,
+        save_load::GridState,
+        terrain::Locked,
+    },
+    history::{Action, History, Snapshot},
+    items::{cables::Cable, packets::PacketType, registry::ItemRegistry, upgrades::UpgradeLevel},
+    levels::Wave,
+    shop::{currency::STARTING_CURRENCY, shop_items::ItemType},
+    user_data::{level_codes_dir, new_file_path, user_levels_dir},
+};
+
+pub mod ui;
+
+// a level setting typed in the editor panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Field {
+    Name,
+    Author,
+    Description,
+    Difficulty,
+    Money,
+    Waves,
+}
+
+#[derive(Resource, Default)]
+pub struct EditorFields {
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    // one of the difficulties, or empty
+    pub difficulty: String,
+    pub money: String,
+    // waves split by ';', packets by ',', each packet as "<type> <seconds to the next one>"
+    pub waves: String,
+    pub focus: Option<Field>,
+    pub erasing: bool,
+    pub status: String,
+    // the last level code exported
+    pub code: String,
+}
+
+impl EditorFields {
+    fn field_mut(&mut self, field: Field) -> &mut String {
+        match field {
+            Field::Name => &mut self.name,
+            Field::Author => &mut self.author,
+            Field::Description => &mut self.description,
+            Field::Difficulty => &mut self.difficulty,
+            Field::Money => &mut self.money,
+            Field::Waves => &mut self.waves,
+        }
+    }
+}
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(EditorUIPlugin);
+        app.init_resource::<EditorFields>();
+        app.add_systems(
+            OnEnter(GameStates::InGame),
+            reset_fields.run_if(in_state(EditorStates::Editing)),
+        );
+        app.add_systems(
+            Update,
+            (
+                on_field,
+                on_eraser,
+                type_text,
+                erase,
+                save_level,
+                export_level,
+                update_editor_ui,
+            )
+                .chain()
+                .run_if(in_state(GameStates::InGame))
+                .run_if(in_state(EditorStates::Editing)),
+        );
+    }
+}
+
+//a new level starts with the usual money and a short wave
+fn reset_fields(mut fields: ResMut<EditorFields>) {
+    *fields = EditorFields {
+        name: "untitled".to_string(),
+        money: STARTING_CURRENCY.to_string(),
+        waves: "Basic 1, Basic 1, Basic 1".to_string(),
+        ..default()
+    };
+}
+
+//typing goes to the clicked field
+fn on_field(mut event: EventReader<FieldBtnPress>, mut fields: ResMut<EditorFields>) {
+    for ev in event.read() {
+        fields.focus = Some(ev.0);
+    }
+}
+
+fn on_eraser(mut event: EventReader<EraserBtnPress>, mut fields: ResMut<EditorFields>) {
+    for _ in event.read() {
+        fields.erasing = !fields.erasing;
+    }
+}
+
+fn type_text(
+    mut events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut fields: ResMut<EditorFields>,
+) {
+    let Some(field) = fields.focus else {
+        events.clear();
+        return;
+    };
+    // shortcuts aren't text
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        events.clear();
+        return;
+    }
+
+    for ev in events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Backspace => {
+                fields.field_mut(field).pop();
+            }
+            Key::Enter => {
+                fields.focus = None;
+            }
+            Key::Space if field != Field::Money => {
+                fields.field_mut(field).push(' ');
+            }
+            Key::Character(text) => {
+                let text = text
+                    .chars()
+                    .filter(|c| field != Field::Money || c.is_ascii_digit());
+                fields.field_mut(field).extend(text);
+            }
+            _ => {}
+        }
+    }
+}
+
+//with the eraser on, clicking an item takes it off the grid
+fn erase(
+    windows: Query<&Window>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    buttons: Query<&Interaction, With<Button>>,
+    cameras: Query<(&GlobalTransform, &Camera)>,
+    items: Query<(&ItemType, Option<&Cable>, Option<&UpgradeLevel>)>,
+    fields: Res<EditorFields>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+    mut commands: Commands,
+) {
+    if !fields.erasing || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    // clicks on the panel aren't for the grid
+    if buttons.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+    let Some(screen_pos) = windows.single().cursor_position() else {
+        return;
+    };
+    let (camera_transform, camera) = cameras.single();
+    let Ok(ray) = camera.viewport_to_world(camera_transform, screen_pos) else {
+        return;
+    };
+
+    let Some(entity) = grid.get_element(ray.origin.truncate()) else {
+        return;
+    };
+    if let Some(item) = Snapshot::of(entity, &grid, &items) {
+        history.record(Action::Remove {
+            entity,
+            item,
+            refund: 0,
+        });
+    }
+    grid.clear_entity(entity);
+    commands.entity(entity).despawn_recursive();
+}
+
+//writes the level to a new file named after it, with the Save as button or Ctrl+S
+fn save_level(
+    mut event: EventReader<SaveBtnPress>,
+    keys: Res<ButtonInput<KeyCode>>,
+    grid: Res<Grid>,
+    items: Query<&ItemType>,
+    cables: Query<&Cable>,
+    locked: Query<(), With<Locked>>,
+    registry: Res<ItemRegistry>,
+    mut fields: ResMut<EditorFields>,
+) {
+    let shortcut = keys.pressed(KeyCode::ControlLeft) && keys.just_pressed(KeyCode::KeyS);
+    if event.read().len() == 0 && !shortcut {
+        return;
+    }
+
+    let state = GridState::from_grid(&grid, &items, &cables, &locked, &registry);
+    let state = match with_settings(state, &fields) {
+        Ok(state) => state,
+        Err(e) => {
+            fields.status = e;
+            return;
+        }
+    };
+
+    let path = new_file_path(&user_levels_dir(), fields.name.trim(), "grid.json");
+    info!("writing to file");
+    fields.status = match fs::create_dir_all(user_levels_dir()).and_then(|_| state.write(&path)) {
+        Ok(()) => format!("saved to {}", path.display()),
+        Err(e) => format!("couldn't save {}: {e}", path.display()),
+    };
+}
+
+//copies the level as a code that can be imported from the levels menu, and keeps it in a file
+fn export_level(
+    mut event: EventReader<ExportBtnPress>,
+    grid: Res<Grid>,
+    items: Query<&ItemType>,
+    cables: Query<&Cable>,
+    locked: Query<(), With<Locked>>,
+    registry: Res<ItemRegistry>,
+    mut fields: ResMut<EditorFields>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+
+    let state = GridState::from_grid(&grid, &items, &cables, &locked, &registry);
+    let state = with_settings(state, &fields).and_then(|state| {
+        state
+            .validate(&registry)
+            .map(|_| state)
+            .map_err(|e| format!("can't be shared: {e}"))
+    });
+    let state = match state {
+        Ok(state) => state,
+        Err(e) => {
+            fields.status = e;
+            return;
+        }
+    };
+
+    let code = export_code(&state);
+    info!("level code: {code}");
+    let copied = match clipboard::copy(&code) {
+        Ok(()) => "code copied".to_string(),
+        Err(e) => format!("couldn't copy the code: {e}"),
+    };
+    let path = new_file_path(&level_codes_dir(), fields.name.trim(), "txt");
+    fields.status =
+        match fs::create_dir_all(level_codes_dir()).and_then(|_| fs::write(&path, &code)) {
+            Ok(()) => format!("{copied}, written to {}", path.display()),
+            Err(e) => format!("{copied}, couldn't write {}: {e}", path.display()),
+        };
+    fields.code = code;
+}
+
+//the grid with the level settings typed in the panel, or what's wrong with them
+fn with_settings(mut state: GridState, fields: &EditorFields) -> Result<GridState, String> {
+    let settings = (
+        fields.name.trim(),
+        parse_difficulty(&fields.difficulty),
+        fields.money.parse::<i32>(),
+        parse_waves(&fields.waves),
+    );
+    match settings {
+        ("", ..) => return Err("the level needs a name".to_string()),
+        (_, Err(e), ..) => return Err(e),
+        (_, _, Err(_), _) => return Err("starting money has to be a number".to_string()),
+        (.., Err(e)) => return Err(format!("waves: {e}")),
+        (name, Ok(difficulty), Ok(money), Ok(waves)) => {
+            state.meta.title = name.to_string();
+            state.meta.difficulty = difficulty;
+            state.money = Some(money);
+            state.waves = Some(waves);
+        }
+    }
+    state.meta.author = fields.author.trim().to_string();
+    state.meta.description = fields.description.trim().to_string();
+    state.meta.created = today();
+    Ok(state)
+}
+
+//reads the difficulty field, which can be left empty
+fn parse_difficulty(text: &str) -> Result<Option<Difficulty>, String> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    Difficulty::ALL
+        .into_iter()
+        .find(|d| format!("{d:?}").eq_ignore_ascii_case(text.trim()))
+        .map(Some)
+        .ok_or_else(|| format!("unknown difficulty '{}'", text.trim()))
+}
+
+//reads the waves field, e.g. "Basic 1, Basic 1; Mid 0.5, Heavy 2"
+pub fn parse_waves(text: &str) -> Result<Vec<Wave>, String> {
+    let mut waves = Vec::new();
+
+    for wave in text.split(';') {
+        let mut packets = Vec::new();
+        for packet in wave.split(',') {
+            let mut words = packet.split_whitespace();
+            let (Some(name), Some(seconds), None) = (words.next(), words.next(), words.next())
+            else {
+                return Err(format!(
+                    "'{}' should be a packet type and seconds",
+                    packet.trim()
+                ));
+            };
+            let Some(packet_type) = PacketType::ALL
+                .into_iter()
+                .find(|t| format!("{t:?}").eq_ignore_ascii_case(name))
+            else {
+                return Err(format!("unknown packet type '{name}'"));
+            };
+            let Ok(seconds) = seconds.parse::<f32>() else {
+                return Err(format!("'{seconds}' isn't a number of seconds"));
+            };
+            if !seconds.is_finite() || seconds < 0. {
+                return Err(format!("'{seconds}' can't be a wait"));
+            }
+            packets.push((packet_type, seconds));
+        }
+        waves.push(Wave::new(packets));
+    }
+    Ok(waves)
+}
+
+fn update_editor_ui(fields: Res<EditorFields>, mut texts: Query<(&mut Text, &EditorText)>) {
+    if !fields.is_changed() {
+        return;
+    }
+
+    for (mut text, editor_text) in &mut texts {
+        text.0 = match editor_text {
+            EditorText::Field(field) => {
+                let (label, value) = match field {
+                    Field::Name => ("Name", &fields.name),
+                    Field::Author => ("Author", &fields.author),
+                    Field::Description => ("About", &fields.description),
+                    Field::Difficulty => ("Difficulty", &fields.difficulty),
+                    Field::Money => ("Money", &fields.money),
+                    Field::Waves => ("Waves", &fields.waves),
+                };
+                let cursor = match fields.focus == Some(*field) {
+                    true => "_",
+                    false => "",
+                };
+                format!("{label}: {value}{cursor}")
+            }
+            EditorText::Eraser => match fields.erasing {
+                true => "Eraser: on".to_string(),
+                false => "Eraser: off".to_string(),
+            },
+            EditorText::Status => fields.status.clone(),
+            EditorText::Code => fields.code.clone(),
+        };
+    }
+}
@@ -0,0 +1,223 @@
+use bevy::color::palettes::basic::*;
+use bevy::prelude::*;
+
+use crate::game::{EditorStates, GameStates, InGame};
+
+use super::Field;
+
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
+
+#[derive(Event)]
+pub struct FieldBtnPress(pub Field);
+
+#[derive(Event)]
+pub struct EraserBtnPress;
+
+#[derive(Event)]
+pub struct SaveBtnPress;
+
+#[derive(Event)]
+pub struct ExportBtnPress;
+
+#[derive(Component)]
+pub enum ButtonType {
+    Field(Field),
+    Eraser,
+    Save,
+    Export,
+}
+
+#[derive(Component)]
+#[require(InGame)]
+pub struct EditorPanel;
+
+// text of a button, kept up to date with the editor
+#[derive(Component)]
+pub enum EditorText {
+    Field(Field),
+    Eraser,
+    Status,
+    Code,
+}
+
+pub struct EditorUIPlugin;
+
+impl Plugin for EditorUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FieldBtnPress>();
+        app.add_event::<EraserBtnPress>();
+        app.add_event::<SaveBtnPress>();
+        app.add_event::<ExportBtnPress>();
+        app.add_systems(
+            OnEnter(GameStates::InGame),
+            setup.run_if(in_state(EditorStates::Editing)),
+        );
+        app.add_systems(
+            Update,
+            button_system
+                .run_if(in_state(GameStates::InGame))
+                .run_if(in_state(EditorStates::Editing)),
+        );
+    }
+}
+
+//manages interactions
+fn button_system(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &ButtonType,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut field: EventWriter<FieldBtnPress>,
+    mut eraser: EventWriter<EraserBtnPress>,
+    mut save: EventWriter<SaveBtnPress>,
+    mut export: EventWriter<ExportBtnPress>,
+) {
+    for (interaction, btn_type, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                border_color.0 = RED.into();
+
+                match btn_type {
+                    ButtonType::Field(f) => {
+                        field.send(FieldBtnPress(*f));
+                    }
+                    ButtonType::Eraser => {
+                        eraser.send(EraserBtnPress);
+                    }
+                    ButtonType::Save => {
+                        save.send(SaveBtnPress);
+                    }
+                    ButtonType::Export => {
+                        export.send(ExportBtnPress);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+
+//creates the panel on the right of the screen
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = TextFont {
+        font: asset_server.load("fonts/courbd.ttf"),
+        font_size: 14.0,
+        ..default()
+    };
+    let button = Node {
+        width: Val::Px(260.0),
+        min_height: Val::Px(28.0),
+        border: UiRect::all(Val::Px(2.0)),
+        padding: UiRect::horizontal(Val::Px(6.0)),
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Name::new("editor panel"),
+            EditorPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let buttons = [
+                (
+                    ButtonType::Field(Field::Name),
+                    Some(EditorText::Field(Field::Name)),
+                ),
+                (
+                    ButtonType::Field(Field::Author),
+                    Some(EditorText::Field(Field::Author)),
+                ),
+                (
+                    ButtonType::Field(Field::Description),
+                    Some(EditorText::Field(Field::Description)),
+                ),
+                (
+                    ButtonType::Field(Field::Difficulty),
+                    Some(EditorText::Field(Field::Difficulty)),
+                ),
+                (
+                    ButtonType::Field(Field::Money),
+                    Some(EditorText::Field(Field::Money)),
+                ),
+                (
+                    ButtonType::Field(Field::Waves),
+                    Some(EditorText::Field(Field::Waves)),
+                ),
+                (ButtonType::Eraser, Some(EditorText::Eraser)),
+                (ButtonType::Save, None),
+                (ButtonType::Export, None),
+            ];
+            for (btn_type, text) in buttons {
+                let label = match btn_type {
+                    ButtonType::Export => "Export code",
+                    _ => "Save as",
+                };
+                parent
+                    .spawn((
+                        Button,
+                        btn_type,
+                        button.clone(),
+                        BorderColor(Color::BLACK),
+                        BackgroundColor(NORMAL_BUTTON),
+                    ))
+                    .with_children(|button| {
+                        let mut label = button.spawn((
+                            Text::new(label),
+                            font.clone(),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                        if let Some(text) = text {
+                            label.insert(text);
+                        }
+                    });
+            }
+            // what the last save did
+            parent.spawn((
+                EditorText::Status,
+                Text::new(""),
+                font.clone(),
+                Node {
+                    max_width: Val::Px(260.0),
+                    ..default()
+                },
+            ));
+            // the last exported code, to copy by hand when the clipboard doesn't work
+            parent.spawn((
+                EditorText::Code,
+                Text::new(""),
+                TextFont {
+                    font_size: 11.0,
+                    ..font.clone()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    max_width: Val::Px(260.0),
+                    ..default()
+                },
+            ));
+        });
+}
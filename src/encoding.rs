@@ -0,0 +1,122 @@
+use std::{fs, path::Path};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+// files ending in this are written with bincode, anything else is JSON
+pub const BINARY_EXTENSION: &str = ".bin";
+
+// start of every binary file, followed by the version of the data, its checksum and the data
+const MAGIC: &[u8; 4] = b"PKTR";
+const HEADER_LEN: usize = 12;
+
+// JSON is for files people write, binary for the ones the game writes for itself
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Json,
+    Binary,
+}
+
+impl Format {
+    pub fn of(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().to_string_lossy().ends_with(BINARY_EXTENSION) {
+            true => Format::Binary,
+            false => Format::Json,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    NotBinary,
+    Version(u32),
+    Checksum,
+    Bincode(bincode::error::DecodeError),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::NotBinary => write!(f, "not a binary file of this game"),
+            DecodeError::Version(version) => {
+                write!(
+                    f,
+                    "written by another version of the game (version {version})"
+                )
+            }
+            DecodeError::Checksum => write!(f, "the file is corrupted, its checksum doesn't match"),
+            DecodeError::Bincode(e) => write!(f, "the file is broken: {e}"),
+            DecodeError::Json(e) => write!(f, "the file is broken: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for std::io::Error {
+    fn from(e: DecodeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+//the bytes of a binary file, `version` is checked again when it's read
+pub fn encode_binary<T: Serialize>(value: &T, version: u32) -> Vec<u8> {
+    let data = bincode::serde::encode_to_vec(value, bincode::config::standard())
+        .expect("game data always encodes");
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + data.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&data).to_le_bytes());
+    bytes.extend_from_slice(&data);
+    bytes
+}
+
+pub fn decode_binary<T: DeserializeOwned>(bytes: &[u8], version: u32) -> Result<T, DecodeError> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return Err(DecodeError::NotBinary);
+    }
+    let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+    let data = &bytes[HEADER_LEN..];
+
+    if word(4) != version {
+        return Err(DecodeError::Version(word(4)));
+    }
+    if word(8) != crc32fast::hash(data) {
+        return Err(DecodeError::Checksum);
+    }
+    bincode::serde::decode_from_slice(data, bincode::config::standard())
+        .map(|(value, _)| value)
+        .map_err(DecodeError::Bincode)
+}
+
+pub fn encode<T: Serialize>(value: &T, format: Format, version: u32) -> Vec<u8> {
+    match format {
+        Format::Json => serde_json::to_vec(value).expect("game data always encodes"),
+        Format::Binary => encode_binary(value, version),
+    }
+}
+
+pub fn decode<T: DeserializeOwned>(
+    bytes: &[u8],
+    format: Format,
+    version: u32,
+) -> Result<T, DecodeError> {
+    match format {
+        Format::Json => serde_json::from_slice(bytes).map_err(DecodeError::Json),
+        Format::Binary => decode_binary(bytes, version),
+    }
+}
+
+//writes a file in the format its extension asks for
+pub fn write_file<T: Serialize>(
+    path: impl AsRef<Path>,
+    value: &T,
+    version: u32,
+) -> std::io::Result<()> {
+    fs::write(&path, encode(value, Format::of(&path), version))
+}
+
+pub fn read_file<T: DeserializeOwned>(path: impl AsRef<Path>, version: u32) -> std::io::Result<T> {
+    Ok(decode(&fs::read(&path)?, Format::of(&path), version)?)
+}
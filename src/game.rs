@@ -1,10 +1,10 @@
-use std::{fs::File, io::Read};
-
 use bevy::prelude::*;
 
-use crate::{grid::Grid, health::Health, shop::currency::Currency};
-
-pub const HIGHEST_LEVEL_PATH: &str = "assets/highestlvl";
+use crate::{
+    grid::Grid,
+    health::Health,
+    shop::currency::{Currency, STARTING_CURRENCY},
+};
 
 #[derive(serde::Serialize, serde::Deserialize, Resource)]
 pub struct HighestLevel {
@@ -12,13 +12,15 @@ pub struct HighestLevel {
 }
 
 #[derive(serde::Serialize, serde::Deserialize, States, Debug, Clone, PartialEq, Eq, Hash, Copy)]
-#[serde(into = "u8", from = "u8")]
+#[serde(into = "u8", try_from = "u8")]
 pub enum GameLevels {
     Sandbox,
     Easy,
     Medium,
     Hard,
     Expert,
+    // a level from outside the campaign, its grid is in CustomLevel
+    Custom,
 }
 impl From<GameLevels> for u8 {
     fn from(value: GameLevels) -> u8 {
@@ -27,15 +29,18 @@ impl From<GameLevels> for u8 {
 }
 
 //all the possible levels
-impl From<u8> for GameLevels {
-    fn from(value: u8) -> GameLevels {
+impl TryFrom<u8> for GameLevels {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<GameLevels, String> {
         match value {
-            0 => GameLevels::Sandbox,
-            1 => GameLevels::Easy,
-            2 => GameLevels::Medium,
-            3 => GameLevels::Hard,
-            4 => GameLevels::Expert,
-            _ => panic!("Invalid value for GameLevels: {}", value),
+            0 => Ok(GameLevels::Sandbox),
+            1 => Ok(GameLevels::Easy),
+            2 => Ok(GameLevels::Medium),
+            3 => Ok(GameLevels::Hard),
+            4 => Ok(GameLevels::Expert),
+            5 => Ok(GameLevels::Custom),
+            _ => Err(format!("there's no level {value}")),
         }
     }
 }
@@ -50,6 +55,7 @@ impl GameLevels {
             GameLevels::Medium => "medium.grid.json",
             GameLevels::Hard => "hard.grid.json",
             GameLevels::Expert => "expert.grid.json",
+            GameLevels::Custom => "custom.grid.json",
         });
         s1
     }
@@ -65,10 +71,20 @@ pub enum GameStates {
     VictoryScreen,
 }
 
-#[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
-pub enum BuildStates {
-    Release,
-    Internal,
+// whether the grid is being played or built in the level editor
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Copy)]
+pub enum EditorStates {
+    Playing,
+    Editing,
+}
+impl EditorStates {
+    //everything is free in the editor
+    pub fn price(&self, price: u32) -> u32 {
+        match self {
+            EditorStates::Playing => price,
+            EditorStates::Editing => 0,
+        }
+    }
 }
 
 #[derive(Component, Default)]
@@ -83,7 +99,7 @@ impl Plugin for GamePlugin {
         });
         app.insert_state(GameStates::Start);
         app.insert_state(GameLevels::Sandbox);
-        app.insert_state(BuildStates::Release);
+        app.insert_state(EditorStates::Playing);
 
         app.add_systems(Update, start_state.run_if(in_state(GameStates::Start)));
         app.add_systems(Update, main_menu_on_escape);
@@ -93,32 +109,26 @@ impl Plugin for GamePlugin {
 }
 
 // runs before entering main menu
-fn start_state(mut next_state: ResMut<NextState<GameStates>>, mut highest: ResMut<HighestLevel>) {
+fn start_state(mut next_state: ResMut<NextState<GameStates>>) {
     next_state.set(GameStates::MainMenu);
-
-    let Ok(mut file) = File::open(HIGHEST_LEVEL_PATH) else {
-        return;
-    };
-    let mut contents = Vec::<u8>::new();
-    file.read_to_end(&mut contents).ok();
-
-    highest.highest = contents[0].into();
 }
 
 // runs when exiting game state
-fn despawn_game(
+pub fn despawn_game(
     mut commands: Commands,
     game: Query<Entity, With<InGame>>,
     mut grid: ResMut<Grid>,
     mut health: ResMut<Health>,
     mut currency: ResMut<Currency>,
+    mut editor: ResMut<NextState<EditorStates>>,
 ) {
     for game_object in &game {
         commands.entity(game_object).try_despawn_recursive();
     }
     grid.reset();
     health.value = 100;
-    currency.value = 300;
+    currency.value = STARTING_CURRENCY;
+    editor.set(EditorStates::Playing);
 }
 fn main_menu_on_escape(
     keys: Res<ButtonInput<KeyCode>>,
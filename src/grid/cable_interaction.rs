@@ -1,16 +1,18 @@
-use bevy::{math::vec3, prelude::*};
+use bevy::{
+    math::{uvec2, vec3},
+    prelude::*,
+};
 use std::f32::consts::PI;
 
 use super::{Grid, interaction::can_place_item};
 use crate::items::cables::{Cable, CableDirection};
-use crate::items::enemy_pc::EnemyPC;
-use crate::items::pcs::PC;
-use crate::items::routers::Router;
-use crate::items::servers::Server;
-use crate::items::switches::Switch;
+use crate::items::pcs::Destroyed;
+use crate::items::registry::ItemRegistry;
 use crate::shop::shop_items::ShopPosition;
 use crate::{
     camera::SPRITE_SIZE,
+    game::EditorStates,
+    history::{Action, History, Snapshot},
     shop::{
         currency::{Currency, UpdateCurrencyEvent},
         shop_items::ItemType,
@@ -41,27 +43,26 @@ impl Plugin for CableInteractionPlugin {
 pub fn drop_cable(
     trigger: Trigger<Pointer<DragEnd>>,
     mut transforms: Query<(&mut Transform, &ShopPosition, &ItemType)>,
-    mut grid_vals: Query<
-        Entity,
-        Or<(
-            With<PC>,
-            With<Switch>,
-            With<Router>,
-            With<EnemyPC>,
-            With<Server>,
-        )>,
-    >,
+    grid_vals: Query<&ItemType, (Without<ShopPosition>, Without<Destroyed>)>,
+    registry: Res<ItemRegistry>,
     grid: ResMut<Grid>,
     mut cable: ResMut<CableOrigin>,
     currency: Res<Currency>,
+    editor: Res<State<EditorStates>>,
     mut cable_state: ResMut<NextState<CableState>>,
 ) {
     let Ok((mut transform, shop_pos, item_type)) = transforms.get_mut(trigger.entity()) else {
         return;
     };
     transform.translation.z = 0.;
-    if can_place_item(&transform, item_type, &grid, &currency)
-        && cable_can_connect(&transform.translation.truncate(), &grid, &mut grid_vals)
+    let price = editor.price(registry.get(*item_type).price);
+    if can_place_item(&transform, price, &grid, &currency)
+        && cable_can_connect(
+            &transform.translation.truncate(),
+            &grid,
+            &grid_vals,
+            &registry,
+        )
     {
         cable_state.set(CableState::Cabling);
         cable.0 = transform.translation.truncate();
@@ -75,39 +76,23 @@ pub fn drop_cable(
 fn cable_can_connect(
     pos: &Vec2,
     grid: &ResMut<Grid>,
-    grid_vals: &mut Query<
-        Entity,
-        Or<(
-            With<PC>,
-            With<Switch>,
-            With<Router>,
-            With<EnemyPC>,
-            With<Server>,
-        )>,
-    >,
+    grid_vals: &Query<&ItemType, (Without<ShopPosition>, Without<Destroyed>)>,
+    registry: &ItemRegistry,
 ) -> bool {
     let Some(entity) = grid.get_element(*pos) else {
         return false;
     };
-    let Ok(_) = grid_vals.get_mut(entity) else {
+    let Ok(item_type) = grid_vals.get(entity) else {
         return false;
     };
-    true
+    registry.get(*item_type).connectable
 }
 
 pub fn click_cable(
     windows: Query<&Window>,
     mouse: Res<ButtonInput<MouseButton>>,
-    mut grid_vals: Query<
-        Entity,
-        Or<(
-            With<PC>,
-            With<Switch>,
-            With<Router>,
-            With<EnemyPC>,
-            With<Server>,
-        )>,
-    >,
+    grid_vals: Query<&ItemType, (Without<ShopPosition>, Without<Destroyed>)>,
+    registry: Res<ItemRegistry>,
     mut grid: ResMut<Grid>,
     cameras: Query<(&GlobalTransform, &Camera)>,
     cable: Res<CableOrigin>,
@@ -115,6 +100,8 @@ pub fn click_cable(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     currency: Res<Currency>,
+    editor: Res<State<EditorStates>>,
+    mut history: ResMut<History>,
     mut writer: EventWriter<UpdateCurrencyEvent>,
 ) {
     if !mouse.just_pressed(MouseButton::Left) {
@@ -136,7 +123,7 @@ pub fn click_cable(
     if !grid.inside_grid(pos) {
         return;
     }
-    if !cable_can_connect(&pos, &grid, &mut grid_vals) {
+    if !cable_can_connect(&pos, &grid, &grid_vals, &registry) {
         return;
     }
 
@@ -147,7 +134,8 @@ pub fn click_cable(
         return;
     }
 
-    let price = pos1.as_vec2().distance(pos2.as_vec2()) as i32;
+    let price = pos1.as_vec2().distance(pos2.as_vec2()) as i32
+        * editor.price(registry.get(ItemType::Cable).price) as i32;
 
     if currency.value < price {
         //if you can't afford a cable
@@ -162,16 +150,29 @@ pub fn click_cable(
         CableDirection::Vertical
     };
 
-    spawn_cable(
+    let Some(cable) = spawn_cable(
         rect,
         &mut commands,
         &asset_server,
         CableSpawnMode::CutSides,
         &mut grid,
         direction,
-    );
-
-    writer.send(UpdateCurrencyEvent(-1 * price)); //pay 1 credit for each cable
+    ) else {
+        return;
+    };
+    writer.send(UpdateCurrencyEvent(-1 * price)); //pay for each cable
+
+    let rect = grid.bounds(cable).unwrap();
+    history.record(Action::Place {
+        entity: cable,
+        item: Snapshot {
+            item_type: ItemType::Cable,
+            anchor: rect.min,
+            cable: Some((rect, direction)),
+            level: 0,
+        },
+        price,
+    });
 }
 
 #[derive(Eq, PartialEq)]
@@ -183,7 +184,7 @@ pub enum CableSpawnMode {
 pub fn spawn_cable(
     mut rect: URect,
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
+    asset_server: &AssetServer,
     mode: CableSpawnMode,
     grid: &mut Grid,
     dir: CableDirection,
@@ -198,23 +199,31 @@ pub fn spawn_cable(
         }
     }
 
+    if rect.min.x > rect.max.x || rect.min.y > rect.max.y {
+        return None; // nothing left between the two ends
+    }
+
+    let mut cells = Vec::new();
     for x in rect.min.x..rect.max.x + 1 {
         for y in rect.min.y..rect.max.y + 1 {
             if grid.grid[x as usize][y as usize].is_some() {
                 return None; // cannot place over existing entities
             }
+            if !grid.terrain_at(uvec2(x, y)).buildable() {
+                return None; // nor through walls
+            }
+            cells.push(uvec2(x, y));
         }
     }
 
     let cable_parent = commands
         .spawn((
-            Cable { dir },
-            ItemType::Cable(dir),
+            Cable { dir, rect },
+            ItemType::Cable,
             Name::new("Cable parent"),
             Transform::default(),
             Visibility::Visible,
         ))
-        .insert(Cable { dir })
         .id();
 
     let rotation = match dir {
@@ -223,27 +232,21 @@ pub fn spawn_cable(
         CableDirection::Vertical => Quat::from_rotation_z(PI / 2.),
     };
 
-    for x in rect.min.x..rect.max.x + 1 {
-        for y in rect.min.y..rect.max.y + 1 {
-            grid.grid[x as usize][y as usize] = Some(cable_parent);
-        }
-    }
+    grid.occupy(cells.iter().copied(), cable_parent);
 
-    for x in rect.min.x..rect.max.x + 1 {
-        for y in rect.min.y..rect.max.y + 1 {
-            commands
-                .spawn((
-                    Sprite::from_image(asset_server.load("cable.png")),
-                    Transform::from_translation(vec3(
-                        x as f32 * SPRITE_SIZE,
-                        y as f32 * SPRITE_SIZE,
-                        1.,
-                    ))
-                    .with_rotation(rotation),
-                    Name::new("Cable child"),
+    for &UVec2 { x, y } in &cells {
+        commands
+            .spawn((
+                Sprite::from_image(asset_server.load("cable.png")),
+                Transform::from_translation(vec3(
+                    x as f32 * SPRITE_SIZE,
+                    y as f32 * SPRITE_SIZE,
+                    1.,
                 ))
-                .set_parent(cable_parent);
-        }
+                .with_rotation(rotation),
+                Name::new("Cable child"),
+            ))
+            .set_parent(cable_parent);
     }
 
     Some(cable_parent)
@@ -0,0 +1,139 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value};
+
+use crate::encoding::DecodeError;
+
+// version of the grid files this game writes, older ones are migrated on load
+pub const GRID_VERSION: u32 = 2;
+
+// upgrades a grid file from the version at its index to the next one
+const MIGRATIONS: [fn(&mut Map<String, Value>); GRID_VERSION as usize] = [v0_to_v1, v1_to_v2];
+
+// how hard a level is meant to be, as shown next to it
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+}
+
+// what a level is, for whoever picks it
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub struct GridMeta {
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    pub difficulty: Option<Difficulty>,
+    // day the level was first saved, as YYYY-MM-DD
+    pub created: String,
+}
+
+#[derive(Debug)]
+pub enum GridError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Binary(DecodeError),
+    NotAGrid,
+    Newer(u64),
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GridError::Io(e) => write!(f, "couldn't read the grid file: {e}"),
+            GridError::Json(e) => write!(f, "the grid file is broken: {e}"),
+            GridError::Binary(e) => write!(f, "couldn't read the binary grid: {e}"),
+            GridError::NotAGrid => write!(f, "the file isn't a grid"),
+            GridError::Newer(version) => write!(
+                f,
+                "the grid file is from a newer version of the game (format {version}, this one reads up to {GRID_VERSION})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl From<std::io::Error> for GridError {
+    fn from(e: std::io::Error) -> Self {
+        GridError::Io(e)
+    }
+}
+
+impl From<DecodeError> for GridError {
+    fn from(e: DecodeError) -> Self {
+        GridError::Binary(e)
+    }
+}
+
+impl From<serde_json::Error> for GridError {
+    fn from(e: serde_json::Error) -> Self {
+        GridError::Json(e)
+    }
+}
+
+//brings a grid file of any known version up to the current one
+pub fn migrate(mut value: Value) -> Result<Value, GridError> {
+    let Some(file) = value.as_object_mut() else {
+        return Err(GridError::NotAGrid);
+    };
+    // files from before versioning have no version
+    let version = match file.get("version") {
+        None => 0,
+        Some(v) => v.as_u64().ok_or(GridError::NotAGrid)?,
+    };
+    if version > GRID_VERSION as u64 {
+        return Err(GridError::Newer(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(file);
+    }
+    file.insert("version".to_string(), GRID_VERSION.into());
+    Ok(value)
+}
+
+//the level name of the editor moved into the metadata
+fn v0_to_v1(file: &mut Map<String, Value>) {
+    let mut meta = Map::new();
+    if let Some(name) = file.remove("name") {
+        meta.insert("title".to_string(), name);
+    }
+    file.insert("meta".to_string(), meta.into());
+}
+
+//the loss rules moved into the grid, json files without them get the default ones
+fn v1_to_v2(_file: &mut Map<String, Value>) {}
+
+//the current date, as written in the metadata
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let days = (secs / 86400) as i64;
+
+    //days since 1970 to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
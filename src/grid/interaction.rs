@@ -1,19 +1,32 @@
 use bevy::{math::vec3, prelude::*};
 
-use super::{Grid, cable_interaction::drop_cable};
+use super::{Grid, cable_interaction::drop_cable, footprint_center};
 use crate::{
-    camera::{SCALE, SPRITE_SIZE},
+    camera::SPRITE_SIZE,
+    game::{EditorStates, GameStates, InGame},
+    history::{Action, History, Snapshot},
+    items::registry::ItemRegistry,
     shop::{
         currency::{Currency, UpdateCurrencyEvent},
         shop_items::{ItemType, ShopPosition},
     },
 };
 
+// highlights the cells a dragged item would cover, green if it can go there
+#[derive(Component)]
+#[require(InGame)]
+pub struct FootprintPreview;
+
 pub struct InteractionPlugin;
 
 impl Plugin for InteractionPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, make_interactable);
+        app.add_systems(OnEnter(GameStates::InGame), init_footprint_preview);
+        app.add_systems(
+            Update,
+            preview_footprint.run_if(in_state(GameStates::InGame)),
+        );
     }
 }
 
@@ -23,7 +36,7 @@ pub fn make_interactable(
 ) {
     for (item, item_type) in &shop_items {
         match item_type {
-            ItemType::Cable(_) => {
+            ItemType::Cable => {
                 commands.entity(item).observe(drag_item).observe(drop_cable);
             }
             _ => {
@@ -36,13 +49,15 @@ pub fn make_interactable(
 pub fn drag_item(
     trigger: Trigger<Pointer<Drag>>,
     mut transforms: Query<&mut Transform, With<ShopPosition>>,
+    projection: Single<&OrthographicProjection, With<Camera2d>>,
 ) {
     let Ok(mut transform) = transforms.get_mut(trigger.entity()) else {
         return;
     };
     let drag = trigger.event();
     transform.translation.z = 20.;
-    transform.translation += vec3(drag.delta.x * SCALE, drag.delta.y * -SCALE, 0.0);
+    let scale = projection.scale;
+    transform.translation += vec3(drag.delta.x * scale, drag.delta.y * -scale, 0.0);
 }
 
 pub fn drop_item(
@@ -51,6 +66,9 @@ pub fn drop_item(
     mut commands: Commands,
     mut grid: ResMut<Grid>,
     currency: Res<Currency>,
+    registry: Res<ItemRegistry>,
+    editor: Res<State<EditorStates>>,
+    mut history: ResMut<History>,
     mut writer: EventWriter<UpdateCurrencyEvent>,
 ) {
     let Ok((mut transform, name, shop_pos, item_type, sprite)) =
@@ -59,23 +77,34 @@ pub fn drop_item(
         return;
     };
     transform.translation.z = 0.;
-    if can_place_item(&transform, item_type, &grid, &currency)
-        && grid.on_empty_cell(transform.translation.truncate())
-    {
-        let pos = grid
-            .world_to_grid(transform.translation.truncate())
-            .unwrap();
+    let info = registry.get(*item_type);
+    let price = editor.price(info.price);
+    let anchor = grid.footprint_anchor(transform.translation.truncate(), info.footprint);
 
+    if let Some(anchor) = anchor
+        && currency.value >= price as i32
+        && grid.footprint_free(anchor, info.footprint)
+    {
         let mut obj = commands.spawn((
             name.clone(),
             sprite.clone(),
-            item_type.clone(),
-            Transform::from_translation(pos.extend(0).as_vec3() * SPRITE_SIZE),
+            *item_type,
+            Transform::from_translation(footprint_center(anchor, info.footprint).extend(0.)),
         ));
-        item_type.add_component(&mut obj);
+        (info.spawn)(&mut obj);
 
-        grid.grid[pos.x as usize][pos.y as usize] = Some(obj.id());
-        writer.send(UpdateCurrencyEvent(-1 * item_type.price() as i32));
+        grid.place(anchor, info.footprint, obj.id());
+        writer.send(UpdateCurrencyEvent(-1 * price as i32));
+        history.record(Action::Place {
+            entity: obj.id(),
+            item: Snapshot {
+                item_type: *item_type,
+                anchor,
+                cable: None,
+                level: 0,
+            },
+            price: price as i32,
+        });
     }
 
     // snap back:
@@ -84,12 +113,74 @@ pub fn drop_item(
 
 pub fn can_place_item(
     transform: &Mut<Transform>,
-    item_type: &ItemType,
+    price: u32,
     grid: &ResMut<Grid>,
     currency: &Res<Currency>,
 ) -> bool {
-    if currency.value < item_type.price() as i32 {
+    if currency.value < price as i32 {
         return false; //if you can't afford it
     }
-    grid.inside_grid(transform.translation.truncate())
+    let pos = transform.translation.truncate();
+    grid.inside_grid(pos) && grid.terrain(pos).buildable()
+}
+
+fn init_footprint_preview(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        FootprintPreview,
+        Transform::default(),
+        MeshMaterial2d(materials.add(Color::NONE)),
+        Mesh2d(meshes.add(Rectangle::new(1., 1.))),
+        Visibility::Hidden,
+    ));
+}
+
+fn preview_footprint(
+    shop_items: Query<(&Transform, &ShopPosition, &ItemType), Without<FootprintPreview>>,
+    mut preview: Single<
+        (
+            &mut Transform,
+            &mut Visibility,
+            &MeshMaterial2d<ColorMaterial>,
+        ),
+        With<FootprintPreview>,
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    grid: Res<Grid>,
+    registry: Res<ItemRegistry>,
+    currency: Res<Currency>,
+    editor: Res<State<EditorStates>>,
+) {
+    let (transform, visibility, material) = &mut *preview;
+
+    //the shop item that isn't in its shop slot is the one being dragged
+    let dragged = shop_items.iter().find(|(t, shop_pos, item_type)| {
+        **item_type != ItemType::Cable && t.translation.truncate() != shop_pos.0
+    });
+
+    let Some((t, _, item_type)) = dragged else {
+        **visibility = Visibility::Hidden;
+        return;
+    };
+    let info = registry.get(*item_type);
+    let Some(anchor) = grid.footprint_anchor(t.translation.truncate(), info.footprint) else {
+        **visibility = Visibility::Hidden;
+        return;
+    };
+
+    **visibility = Visibility::Visible;
+    transform.translation = footprint_center(anchor, info.footprint).extend(15.);
+    transform.scale = (info.footprint.as_vec2() * SPRITE_SIZE).extend(1.);
+
+    let fits = grid.footprint_free(anchor, info.footprint)
+        && currency.value >= editor.price(info.price) as i32;
+    if let Some(material) = materials.get_mut(&material.0) {
+        material.color = match fits {
+            true => Color::srgba(0.3, 1., 0.3, 0.25),
+            false => Color::srgba(1., 0.2, 0.2, 0.25),
+        };
+    }
 }
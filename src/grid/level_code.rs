@@ -0,0 +1,72 @@
+use std::io::{Read, Write};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+
+use crate::{
+    encoding::Format,
+    grid::{format::GridError, save_load::GridState},
+};
+
+// start of every level code, the number is the version of the code itself
+const CODE_PREFIX: &str = "PT1-";
+// a level bigger than this once unpacked is a broken code
+const MAX_LEVEL_BYTES: u64 = 1 << 20;
+
+#[derive(Debug)]
+pub enum CodeError {
+    NotACode,
+    BadCharacters,
+    Damaged,
+    Tampered,
+    Grid(GridError),
+}
+
+impl std::fmt::Display for CodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CodeError::NotACode => write!(f, "level codes start with {CODE_PREFIX}"),
+            CodeError::BadCharacters => write!(f, "the code has characters that aren't in codes"),
+            CodeError::Damaged => write!(f, "the code is cut short or damaged"),
+            CodeError::Tampered => write!(f, "the code was changed, its checksum doesn't match"),
+            CodeError::Grid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+//the level as a line of text, JSON inside so codes go through the grid migrations
+pub fn export_code(state: &GridState) -> String {
+    let json = serde_json::to_vec(state).expect("grid states always encode");
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&json).expect("writing to memory");
+    let compressed = encoder.finish().expect("writing to memory");
+
+    let mut bytes = crc32fast::hash(&json).to_le_bytes().to_vec();
+    bytes.extend(compressed);
+    format!("{CODE_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+//a level back from its code, line breaks and spaces from pasting are ignored
+pub fn import_code(code: &str) -> Result<GridState, CodeError> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let Some(data) = code.strip_prefix(CODE_PREFIX) else {
+        return Err(CodeError::NotACode);
+    };
+    let bytes = URL_SAFE_NO_PAD
+        .decode(data)
+        .map_err(|_| CodeError::BadCharacters)?;
+    if bytes.len() < 4 {
+        return Err(CodeError::Damaged);
+    }
+    let (checksum, compressed) = bytes.split_at(4);
+
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(MAX_LEVEL_BYTES)
+        .read_to_end(&mut json)
+        .map_err(|_| CodeError::Damaged)?;
+    if crc32fast::hash(&json).to_le_bytes() != checksum {
+        return Err(CodeError::Tampered);
+    }
+    GridState::parse(&json, Format::Json).map_err(CodeError::Grid)
+}
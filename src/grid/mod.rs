@@ -1,44 +1,100 @@
 use bevy::{
     math::{uvec2, vec3},
     prelude::*,
+    utils::HashMap,
 };
 use cable_interaction::CableInteractionPlugin;
 use interaction::InteractionPlugin;
 
 use crate::grid::save_load::SaveLoadPlugin;
+use crate::grid::terrain::Terrain;
+use crate::grid::topology::TopologyPlugin;
 use crate::{
     camera::SPRITE_SIZE,
     game::{GameStates, InGame},
 };
 
 pub mod cable_interaction;
+pub mod format;
 pub mod interaction;
+pub mod level_code;
 pub mod save_load;
+pub mod terrain;
+pub mod topology;
 
-pub const GRID_N: usize = 30;
-pub const GRID_M: usize = 13;
-#[derive(Default, Resource)]
+// size of grids whose file doesn't declare one
+pub const DEFAULT_GRID_SIZE: UVec2 = UVec2::new(30, 13);
+
+#[derive(Resource)]
 pub struct Grid {
-    grid: [[Option<Entity>; GRID_M]; GRID_N],
+    size: UVec2,
+    grid: Vec<Vec<Option<Entity>>>,
+    // cells covered by every entity on the grid
+    cells: HashMap<Entity, Vec<UVec2>>,
+    terrain: Vec<Vec<Terrain>>,
+    // changes with the size or the terrain, so the cells know to respawn
+    layout: u32,
+}
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRID_SIZE)
+    }
 }
 
 //make the matrix grid
 impl Grid {
-    pub fn reset(&mut self) {
-        for i in 0..GRID_N {
-            for j in 0..GRID_M {
-                self.grid[i][j] = None;
-            }
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            grid: vec![vec![None; size.y as usize]; size.x as usize],
+            cells: HashMap::new(),
+            terrain: vec![vec![Terrain::Floor; size.y as usize]; size.x as usize],
+            layout: 0,
         }
     }
 
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    //empties the grid and gives it a new size
+    pub fn resize(&mut self, size: UVec2) {
+        let layout = self.layout + 1;
+        *self = Self::new(size);
+        self.layout = layout;
+    }
+
+    pub fn reset(&mut self) {
+        self.resize(DEFAULT_GRID_SIZE);
+    }
+
+    //checks if a cell is part of the grid
+    pub fn contains(&self, cell: UVec2) -> bool {
+        cell.x < self.size.x && cell.y < self.size.y
+    }
+
+    pub fn terrain_at(&self, cell: UVec2) -> Terrain {
+        self.terrain[cell.x as usize][cell.y as usize]
+    }
+
+    //terrain under a world position, floor outside the grid
+    pub fn terrain(&self, pos: Vec2) -> Terrain {
+        self.world_to_grid(pos)
+            .map_or(Terrain::Floor, |cell| self.terrain_at(cell))
+    }
+
+    pub fn set_terrain(&mut self, cell: UVec2, terrain: Terrain) {
+        self.terrain[cell.x as usize][cell.y as usize] = terrain;
+        self.layout += 1;
+    }
+
     fn inside_grid(&self, mut pos: Vec2) -> bool {
         //checks if a position is actually in the grid
         pos += SPRITE_SIZE / 2.;
-        if pos.x >= GRID_N as f32 * SPRITE_SIZE || pos.x < 0. {
+        if pos.x >= self.size.x as f32 * SPRITE_SIZE || pos.x < 0. {
             return false;
         }
-        if pos.y >= GRID_M as f32 * SPRITE_SIZE || pos.y < 0. {
+        if pos.y >= self.size.y as f32 * SPRITE_SIZE || pos.y < 0. {
             return false;
         }
         return true;
@@ -65,53 +121,129 @@ impl Grid {
         self.grid[pos.x as usize][pos.y as usize]
     }
 
-    pub fn cable_rect(&self, cable: Entity, pos: UVec2) -> URect {
-        let mut rect = URect::new(pos.x, pos.y, pos.x, pos.y);
+    //checks if an item covering `footprint` cells fits with its bottom left cell at `anchor`
+    pub fn footprint_free(&self, anchor: UVec2, footprint: UVec2) -> bool {
+        footprint_cells(anchor, footprint).all(|cell| {
+            self.contains(cell)
+                && self.grid[cell.x as usize][cell.y as usize].is_none()
+                && self.terrain_at(cell).buildable()
+        })
+    }
 
-        for i in 0..GRID_N {
-            for j in 0..GRID_M {
-                self.grid[i][j].map(|e| {
-                    if e == cable {
-                        rect.min.x = rect.min.x.min(i as u32);
-                        rect.min.y = rect.min.y.min(j as u32);
+    pub fn place(&mut self, anchor: UVec2, footprint: UVec2, entity: Entity) {
+        self.occupy(footprint_cells(anchor, footprint), entity);
+    }
 
-                        rect.max.x = rect.max.x.max(i as u32);
-                        rect.max.y = rect.max.y.max(j as u32);
-                    }
-                });
-            }
+    //puts an entity on the given cells, keeping track of them in the index
+    pub fn occupy(&mut self, cells: impl IntoIterator<Item = UVec2>, entity: Entity) {
+        let occupied = self.cells.entry(entity).or_default();
+        for cell in cells {
+            self.grid[cell.x as usize][cell.y as usize] = Some(entity);
+            occupied.push(cell);
         }
+    }
+
+    //what's on a cell, None outside of the grid
+    pub fn at(&self, cell: UVec2) -> Option<Entity> {
+        self.contains(cell)
+            .then(|| self.grid[cell.x as usize][cell.y as usize])
+            .flatten()
+    }
 
-        rect
+    //cells an entity covers
+    pub fn cells_of(&self, entity: Entity) -> &[UVec2] {
+        self.cells.get(&entity).map_or(&[], Vec::as_slice)
+    }
+
+    //every entity on the grid, with the cells it covers
+    pub fn entities(&self) -> impl Iterator<Item = (Entity, &[UVec2])> {
+        self.cells
+            .iter()
+            .map(|(entity, cells)| (*entity, cells.as_slice()))
+    }
+
+    //smallest rectangle around the cells an entity covers
+    pub fn bounds(&self, entity: Entity) -> Option<URect> {
+        let cells = self.cells_of(entity);
+        let min = cells.iter().copied().reduce(UVec2::min)?;
+        let max = cells.iter().copied().reduce(UVec2::max)?;
+        Some(URect::from_corners(min, max))
+    }
+
+    //removes an item from every cell it covers
+    pub fn clear_entity(&mut self, entity: Entity) {
+        for cell in self.cells.remove(&entity).unwrap_or_default() {
+            self.grid[cell.x as usize][cell.y as usize] = None;
+        }
+    }
+
+    //bottom left cell of an item covering `footprint` cells, centered at `pos`
+    pub fn footprint_anchor(&self, pos: Vec2, footprint: UVec2) -> Option<UVec2> {
+        self.world_to_grid(pos - (footprint.as_vec2() - 1.) / 2. * SPRITE_SIZE)
     }
 }
 
+//world position of the center of an item covering `footprint` cells from `anchor`
+pub fn footprint_center(anchor: UVec2, footprint: UVec2) -> Vec2 {
+    (anchor.as_vec2() + (footprint.as_vec2() - 1.) / 2.) * SPRITE_SIZE
+}
+
+pub fn footprint_cells(anchor: UVec2, footprint: UVec2) -> impl Iterator<Item = UVec2> {
+    (0..footprint.x).flat_map(move |x| (0..footprint.y).map(move |y| anchor + uvec2(x, y)))
+}
+
+// parent of the grid cells and terrain, for a grid of this size
 #[derive(Component)]
 #[require(InGame)]
-pub struct GridRoot;
+pub struct GridRoot {
+    pub size: UVec2,
+    layout: u32,
+}
 
 pub struct GridPlugin;
 
 impl Plugin for GridPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((InteractionPlugin, CableInteractionPlugin, SaveLoadPlugin));
-        app.add_systems(OnEnter(GameStates::InGame), init_grid);
+        app.add_plugins((
+            InteractionPlugin,
+            CableInteractionPlugin,
+            SaveLoadPlugin,
+            TopologyPlugin,
+        ));
+        app.add_systems(Update, init_grid.run_if(in_state(GameStates::InGame)));
         app.init_resource::<Grid>();
     }
 }
 
-pub fn init_grid(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let grid = commands
+//spawns the cells of the grid, again whenever a level with another size or terrain is loaded
+pub fn init_grid(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    grid: Res<Grid>,
+    roots: Query<(Entity, &GridRoot)>,
+) {
+    let size = grid.size();
+    if roots.iter().any(|(_, root)| root.layout == grid.layout) {
+        return;
+    }
+    for (root, _) in &roots {
+        commands.entity(root).despawn_recursive();
+    }
+
+    let root = commands
         .spawn((
             Name::new("Grid"),
-            GridRoot,
+            GridRoot {
+                size,
+                layout: grid.layout,
+            },
             Transform::default(),
             Visibility::Visible,
         ))
         .id();
 
-    for i in 0..GRID_N {
-        for j in 0..GRID_M {
+    for i in 0..size.x {
+        for j in 0..size.y {
             commands
                 .spawn((
                     Sprite::from_image(asset_server.load("grid_cell.png")), //spawn the grid squares
@@ -122,7 +254,23 @@ pub fn init_grid(mut commands: Commands, asset_server: Res<AssetServer>) {
                     )),
                     Name::new("Grid Cell"),
                 ))
-                .set_parent(grid); //as the children of the grid
+                .set_parent(root); //as the children of the grid
+
+            //walls and slow ground go right under the items
+            let Some(sprite) = grid.terrain_at(uvec2(i, j)).sprite_path() else {
+                continue;
+            };
+            commands
+                .spawn((
+                    Sprite::from_image(asset_server.load(sprite)),
+                    Transform::from_translation(vec3(
+                        i as f32 * SPRITE_SIZE,
+                        j as f32 * SPRITE_SIZE,
+                        -0.5,
+                    )),
+                    Name::new("Terrain"),
+                ))
+                .set_parent(root);
         }
     }
 }
@@ -1,61 +1,283 @@
-use crate::camera::SPRITE_SIZE;
-use crate::game::{BuildStates, GameLevels, GameStates};
+use crate::encoding::{self, Format};
+use crate::game::{EditorStates, GameLevels, GameStates};
 use crate::grid::cable_interaction::{CableSpawnMode, spawn_cable};
-use crate::grid::save_load::GridItem::{Cable, EnemyPC, PC, Router, Switch};
-use crate::grid::{GRID_M, GRID_N, Grid};
-use crate::items::cables::CableDirection;
+use crate::grid::format::{GRID_VERSION, GridError, GridMeta, migrate};
+use crate::grid::terrain::{Locked, Terrain, TerrainArea};
+use crate::grid::topology::NodeKind;
+use crate::grid::{DEFAULT_GRID_SIZE, Grid, footprint_center};
+use crate::items::cables::{Cable, CableDirection};
+use crate::items::registry::ItemRegistry;
+use crate::levels::{DestroyedPcRule, LossRule, Wave, WaveManager};
+use crate::savegame::ResumeGame;
+use crate::shop::currency::Currency;
 use crate::shop::shop_items::ItemType;
+use crate::toast::Toast;
+use bevy::asset::{AssetLoader, LoadContext, LoadState, io::Reader};
 use bevy::math::uvec2;
 use bevy::prelude::*;
 use bevy::reflect::erased_serde::__private::serde;
-use bevy::utils::HashSet;
-use bevy_common_assets::json::JsonAssetPlugin;
-use std::fs::File;
-use std::io::{BufWriter, Write};
 use std::path::Path;
 
-#[derive(serde::Serialize, serde::Deserialize, Asset, TypePath, Default)]
+// grids from codes and user files are refused past this many cells on a side
+pub const MAX_GRID_SIDE: u32 = 300;
+
+// key cables are written under, cables are the only item with more than a position
+const CABLE_KEY: &str = "Cable";
+
+#[derive(serde::Serialize, serde::Deserialize, Asset, TypePath, Clone)]
 pub struct GridState {
+    version: u32,
+    #[serde(default)]
+    pub meta: GridMeta,
+    #[serde(default = "default_width")]
+    width: u32,
+    #[serde(default = "default_height")]
+    height: u32,
+    #[serde(default)]
+    terrain: Vec<TerrainArea>,
     items: Vec<GridItem>,
+    // cells of the pre-placed items the player can't sell or upgrade
+    #[serde(default)]
+    locked: Vec<UVec2>,
+    // set by the level editor, levels without them use the built-in values
+    #[serde(default)]
+    pub money: Option<i32>,
+    #[serde(default)]
+    pub waves: Option<Vec<Wave>>,
+    // levels that don't set them are lost with the first PC
+    #[serde(default)]
+    pub loss_rule: LossRule,
+    #[serde(default)]
+    pub destroyed_pc: DestroyedPcRule,
+}
+
+impl GridState {
+    //everything on the grid, as written in grid files
+    pub fn from_grid(
+        grid: &Grid,
+        items: &Query<&ItemType>,
+        cables: &Query<&Cable>,
+        locked: &Query<(), With<Locked>>,
+        registry: &ItemRegistry,
+    ) -> Self {
+        let mut state = GridState {
+            version: GRID_VERSION,
+            meta: GridMeta::default(),
+            width: grid.size().x,
+            height: grid.size().y,
+            terrain: terrain_areas(grid),
+            items: Vec::new(),
+            locked: Vec::new(),
+            money: None,
+            waves: None,
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
+        };
+
+        //the bottom left cell of every item, in column order so files stay stable
+        let mut anchors: Vec<(UVec2, Entity)> = grid
+            .entities()
+            .map(|(entity, cells)| {
+                let anchor = cells.iter().fold(UVec2::MAX, |min, cell| min.min(*cell));
+                (anchor, entity)
+            })
+            .collect();
+        anchors.sort_by_key(|(anchor, _)| (anchor.x, anchor.y));
+
+        for (anchor, entity) in anchors {
+            if locked.contains(entity) {
+                state.locked.push(anchor);
+            }
+
+            let Ok(item_type) = items.get(entity) else {
+                continue;
+            };
+            state.items.push(GridItem::from_type(
+                entity, *item_type, cables, registry, anchor,
+            ));
+        }
+        state
+    }
+
+    //reads a grid file of any version up to the current one
+    //binary grids are only ever written by this version, so they skip the migrations
+    pub fn parse(bytes: &[u8], format: Format) -> Result<Self, GridError> {
+        match format {
+            Format::Json => {
+                let value = migrate(serde_json::from_slice(bytes)?)?;
+                Ok(serde_json::from_value(value)?)
+            }
+            Format::Binary => Ok(encoding::decode_binary(bytes, GRID_VERSION)?),
+        }
+    }
+
+    //whether a grid from outside the game can be played, with the reason when it can't
+    pub fn validate(&self, registry: &ItemRegistry) -> Result<(), String> {
+        let size = uvec2(self.width, self.height);
+        if size.min_element() == 0 || size.max_element() > MAX_GRID_SIDE {
+            return Err(format!("a {}x{} grid is too big or empty", size.x, size.y));
+        }
+
+        let mut kinds = Vec::new();
+        for item in &self.items {
+            //the cell of the item furthest from the origin
+            let (item_type, far) = match item {
+                GridItem::Cable(rect, _) => {
+                    if rect.min.cmpgt(rect.max).any() {
+                        return Err(format!(
+                            "a cable goes backwards from {} to {}",
+                            rect.min, rect.max
+                        ));
+                    }
+                    (ItemType::Cable, rect.max)
+                }
+                GridItem::Item(key, pos) => {
+                    let Some(item_type) = registry.from_key(key) else {
+                        return Err(format!("unknown item '{key}'"));
+                    };
+                    // positions come from files and codes, they can be anything
+                    let footprint = registry.get(item_type).footprint;
+                    let far = pos
+                        .x
+                        .checked_add(footprint.x - 1)
+                        .zip(pos.y.checked_add(footprint.y - 1));
+                    let Some((x, y)) = far else {
+                        return Err(format!("an item at {pos} is outside of the grid"));
+                    };
+                    (item_type, uvec2(x, y))
+                }
+            };
+            if far.cmpge(size).any() {
+                return Err(format!("an item at {far} is outside of the grid"));
+            }
+            kinds.extend(NodeKind::from_item(item_type));
+        }
+
+        if !kinds.contains(&NodeKind::EnemyPc) {
+            return Err("the level has no enemy PC".to_string());
+        }
+        if !kinds.contains(&NodeKind::Pc) {
+            return Err("the level has no PC to protect".to_string());
+        }
+        Ok(())
+    }
+
+    //as JSON, or binary for paths ending in .bin
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        encoding::write_file(path, self, GRID_VERSION)
+    }
+}
+
+// older grid files don't have dimensions
+fn default_width() -> u32 {
+    DEFAULT_GRID_SIZE.x
+}
+fn default_height() -> u32 {
+    DEFAULT_GRID_SIZE.y
+}
+
+// loads grid files through the migrations, instead of straight into a GridState
+#[derive(Default)]
+struct GridLoader;
+
+impl AssetLoader for GridLoader {
+    type Asset = GridState;
+    type Settings = ();
+    type Error = GridError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<GridState, GridError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        GridState::parse(&bytes, Format::of(load_context.path()))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["grid.json", "grid.bin"]
+    }
 }
 
+// the grid of GameLevels::Custom, from a level code or a user file
+#[derive(Resource, Default)]
+pub struct CustomLevel(pub Option<GridState>);
+
 #[derive(Resource)]
 pub struct GridHandle(Option<Handle<GridState>>);
 
-#[derive(serde::Serialize, serde::Deserialize, Asset, TypePath, Debug)]
+// items are written as { "<registry key>": position }, cables as { "Cable": [rect, direction] }
+#[derive(Asset, TypePath, Clone, Debug, PartialEq)]
 pub enum GridItem {
-    PC(UVec2),
-    EnemyPC(UVec2),
-    Router(UVec2),
-    Switch(UVec2),
     Cable(URect, CableDirection),
-    Server(UVec2),
+    Item(String, UVec2),
 }
 
 impl GridItem {
-    fn from_type(entity: Entity, item_type: &ItemType, grid: &Grid, (i, j): (u32, u32)) -> Self {
-        match item_type {
-            ItemType::PC => PC(uvec2(i, j)),
-            ItemType::EnemyPC => EnemyPC(uvec2(i, j)),
-            ItemType::Router => Router(uvec2(i, j)),
-            ItemType::Switch => Switch(uvec2(i, j)),
-            ItemType::Cable(dir) => Cable(grid.cable_rect(entity, uvec2(i, j)), *dir),
-            ItemType::Server => Self::Server(uvec2(i, j)),
+    fn from_type(
+        entity: Entity,
+        item_type: ItemType,
+        cables: &Query<&Cable>,
+        registry: &ItemRegistry,
+        anchor: UVec2,
+    ) -> Self {
+        match cables.get(entity) {
+            Ok(cable) => GridItem::Cable(cable.rect, cable.dir),
+            Err(_) => GridItem::Item(registry.get(item_type).key.to_string(), anchor),
+        }
+    }
+
+    //the registered item type of a grid item, None if the key is unknown
+    pub fn item_type(&self, registry: &ItemRegistry) -> Option<ItemType> {
+        match self {
+            GridItem::Cable(..) => Some(ItemType::Cable),
+            GridItem::Item(key, _) => registry.from_key(key),
         }
     }
 }
 
-impl Into<ItemType> for GridItem {
-    //transforms grid items into item types
-    fn into(self) -> ItemType {
+impl serde::Serialize for GridItem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(1))?;
         match self {
-            GridItem::PC(_) => ItemType::PC,
-            GridItem::EnemyPC(_) => ItemType::EnemyPC,
-            GridItem::Router(_) => ItemType::Router,
-            GridItem::Switch(_) => ItemType::Switch,
-            GridItem::Cable(_, dir) => ItemType::Cable(dir),
-            GridItem::Server(_) => ItemType::Server,
+            GridItem::Cable(rect, dir) => map.serialize_entry(CABLE_KEY, &(rect, dir))?,
+            GridItem::Item(key, pos) => map.serialize_entry(key, pos)?,
         }
+        map.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GridItem {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GridItemVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GridItemVisitor {
+            type Value = GridItem;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map with a single item key")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<GridItem, A::Error> {
+                let Some(key) = map.next_key::<String>()? else {
+                    return Err(serde::de::Error::custom("grid item without a key"));
+                };
+                if key == CABLE_KEY {
+                    let (rect, dir) = map.next_value()?;
+                    return Ok(GridItem::Cable(rect, dir));
+                }
+                Ok(GridItem::Item(key, map.next_value()?))
+            }
+        }
+
+        deserializer.deserialize_map(GridItemVisitor)
     }
 }
 
@@ -64,17 +286,16 @@ pub struct SaveLoadPlugin;
 impl Plugin for SaveLoadPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GridHandle(None));
+        app.init_resource::<CustomLevel>();
 
-        app.add_plugins(JsonAssetPlugin::<GridState>::new(&["grid.json"]));
-
-        app.add_systems(OnEnter(GameStates::InGame), load_on_play);
-        app.add_systems(Update, populate_grid.run_if(in_state(GameStates::InGame)));
+        app.init_asset::<GridState>();
+        app.init_asset_loader::<GridLoader>();
 
         app.add_systems(
-            Update,
-            save.run_if(in_state(BuildStates::Internal))
-                .run_if(in_state(GameStates::InGame)),
+            OnEnter(GameStates::InGame),
+            load_on_play.run_if(not(resource_exists::<ResumeGame>)),
         );
+        app.add_systems(Update, populate_grid.run_if(in_state(GameStates::InGame)));
     }
 }
 
@@ -82,10 +303,32 @@ impl Plugin for SaveLoadPlugin {
 fn load_on_play(
     asset_server: Res<AssetServer>,
     level: Res<State<GameLevels>>,
+    editor: Res<State<EditorStates>>,
+    custom: Res<CustomLevel>,
+    mut grids: ResMut<Assets<GridState>>,
+    mut toasts: EventWriter<Toast>,
+    mut next_state: ResMut<NextState<GameStates>>,
     mut commands: Commands,
 ) {
+    // the editor starts from an empty grid
+    if *editor == EditorStates::Editing {
+        return;
+    }
+    if *level == GameLevels::Custom {
+        let Some(state) = custom.0.clone() else {
+            toasts.send(Toast("there's no custom level to play".to_string()));
+            next_state.set(GameStates::MainMenu);
+            return;
+        };
+        commands.insert_resource(GridHandle(Some(grids.add(state))));
+        return;
+    }
     if !Path::new(&format!("assets/{}", level.level_path())).exists() {
-        info!("path not exist: assets/{}", &level.level_path());
+        toasts.send(Toast(format!(
+            "the level file assets/{} is missing",
+            level.level_path()
+        )));
+        next_state.set(GameStates::MainMenu);
         return;
     }
     commands.insert_resource(GridHandle(Some(asset_server.load(level.level_path()))));
@@ -94,19 +337,21 @@ fn load_on_play(
 pub fn spawn_item(
     pos: UVec2,
     item_type: ItemType,
+    registry: &ItemRegistry,
     grid: &mut Grid,
     asset_server: &AssetServer,
     commands: &mut Commands,
 ) -> Entity {
+    let info = registry.get(item_type);
     let mut entity = commands.spawn((
-        Name::new(item_type.name()),
-        Sprite::from_image(asset_server.load(item_type.sprite_path())),
+        Name::new(info.name),
+        Sprite::from_image(asset_server.load(info.sprite)),
         item_type,
-        Transform::from_translation((pos.as_vec2() * SPRITE_SIZE).extend(0.)),
+        Transform::from_translation(footprint_center(pos, info.footprint).extend(0.)),
     ));
 
-    item_type.add_component(&mut entity);
-    grid.grid[pos.x as usize][pos.y as usize] = Some(entity.id());
+    (info.spawn)(&mut entity);
+    grid.place(pos, info.footprint, entity.id());
 
     entity.id()
 }
@@ -117,123 +362,139 @@ fn populate_grid(
     mut grid_handle: ResMut<GridHandle>,
     mut grids: ResMut<Assets<GridState>>,
     asset_server: Res<AssetServer>,
+    registry: Res<ItemRegistry>,
     mut grid: ResMut<Grid>,
+    mut currency: ResMut<Currency>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut toasts: EventWriter<Toast>,
+    mut next_state: ResMut<NextState<GameStates>>,
 ) {
     let Some(handle) = grid_handle.0.as_ref().map(|a| a.id()) else {
         return;
     };
 
-    let Some(grid_state) = grids.remove(handle) else {
+    //a level that can't be read goes back to the menu instead of showing an empty board
+    if let LoadState::Failed(e) = asset_server.load_state(handle) {
+        toasts.send(Toast(format!("couldn't load the level: {e}")));
+        next_state.set(GameStates::MainMenu);
+        grid_handle.0 = None;
+        return;
+    }
+    let Some(mut grid_state) = grids.remove(handle) else {
         return;
     };
 
-    grid.reset();
-
     grid_handle.0 = None;
 
-    for grid_item in grid_state.items {
-        match grid_item {
-            GridItem::Cable(rect, dir) => {
-                spawn_cable(
-                    rect,
-                    &mut commands,
-                    &asset_server,
-                    CableSpawnMode::Raw,
-                    &mut grid,
-                    dir,
-                );
-            }
-            GridItem::PC(pos) => {
-                spawn_item(
-                    pos,
-                    grid_item.into(),
-                    &mut grid,
-                    &asset_server,
-                    &mut commands,
-                );
-            }
-            GridItem::EnemyPC(pos) => {
-                spawn_item(
-                    pos,
-                    grid_item.into(),
-                    &mut grid,
-                    &asset_server,
-                    &mut commands,
-                );
-            }
-            GridItem::Router(pos) => {
-                spawn_item(
-                    pos,
-                    grid_item.into(),
-                    &mut grid,
-                    &asset_server,
-                    &mut commands,
-                );
-            }
-            GridItem::Switch(pos) => {
-                spawn_item(
-                    pos,
-                    grid_item.into(),
-                    &mut grid,
-                    &asset_server,
-                    &mut commands,
-                );
-            }
-            GridItem::Server(pos) => {
-                spawn_item(
-                    pos,
-                    grid_item.into(),
-                    &mut grid,
-                    &asset_server,
-                    &mut commands,
-                );
+    if let Some(money) = grid_state.money {
+        currency.value = money;
+    }
+    if let Some(level) = wave_manager.level.as_mut() {
+        level.set_rules(grid_state.loss_rule, grid_state.destroyed_pc);
+    }
+    match grid_state.waves.take() {
+        Some(waves) if waves.is_empty() || waves.iter().any(|w| w.packets().is_empty()) => {
+            warn!("grid file has an empty wave, keeping the built-in waves");
+        }
+        Some(waves) => {
+            //starts over, with the waves of the file
+            if let Some(mut level) = wave_manager.level.take() {
+                level.set_waves(waves);
+                *wave_manager = WaveManager::new(level);
             }
         }
+        None => {}
     }
+
+    build_grid(
+        grid_state,
+        &mut grid,
+        &registry,
+        &asset_server,
+        &mut commands,
+    );
 }
 
-fn save(
-    //save the map with Ctrl+S
-    grid: Res<Grid>,
-    keys: Res<ButtonInput<KeyCode>>,
-    items: Query<&ItemType>,
-    level: Res<State<GameLevels>>,
+//lays out the terrain and items of a grid file on an empty grid
+pub fn build_grid(
+    grid_state: GridState,
+    grid: &mut Grid,
+    registry: &ItemRegistry,
+    asset_server: &AssetServer,
+    commands: &mut Commands,
 ) {
-    if keys.pressed(KeyCode::ControlLeft) {
-        if keys.just_pressed(KeyCode::KeyS) {
-            let mut state = GridState::default();
-
-            let mut added_entities: HashSet<Entity> = HashSet::new();
+    grid.resize(uvec2(grid_state.width, grid_state.height));
 
-            for i in 0..GRID_N {
-                for j in 0..GRID_M {
-                    let Some(entity) = grid.grid[i][j] else {
-                        continue;
-                    };
-
-                    if added_entities.contains(&entity) {
-                        continue;
-                    }
-
-                    added_entities.insert(entity);
+    for area in grid_state.terrain {
+        if !grid.contains(area.rect.max) {
+            warn!("terrain outside of the grid: {:?}", area.rect);
+            continue;
+        }
+        for x in area.rect.min.x..area.rect.max.x + 1 {
+            for y in area.rect.min.y..area.rect.max.y + 1 {
+                grid.set_terrain(uvec2(x, y), area.terrain);
+            }
+        }
+    }
 
-                    let item_type = items.get(entity).unwrap();
-                    state.items.push(GridItem::from_type(
-                        entity,
-                        &item_type,
-                        &grid,
-                        (i as u32, j as u32),
-                    ));
+    for grid_item in grid_state.items {
+        match grid_item {
+            GridItem::Cable(rect, dir) => {
+                if !grid.contains(rect.max) {
+                    warn!("cable outside of the grid: {rect:?}");
+                    continue;
+                }
+                spawn_cable(rect, commands, asset_server, CableSpawnMode::Raw, grid, dir);
+            }
+            GridItem::Item(ref key, pos) => {
+                let Some(item_type) = grid_item.item_type(registry) else {
+                    warn!("unknown grid item: {key}");
+                    continue;
+                };
+                if !grid.footprint_free(pos, registry.get(item_type).footprint) {
+                    warn!("{key} doesn't fit at {pos}");
+                    continue;
                 }
+                spawn_item(pos, item_type, registry, grid, asset_server, commands);
             }
+        }
+    }
 
-            info!("writing to file");
+    for cell in grid_state.locked {
+        let Some(item) = grid.at(cell) else {
+            warn!("nothing to lock at {cell}");
+            continue;
+        };
+        commands.entity(item).insert(Locked);
+    }
+}
+
+//terrain as runs of the same terrain along each row
+fn terrain_areas(grid: &Grid) -> Vec<TerrainArea> {
+    let mut areas: Vec<TerrainArea> = Vec::new();
 
-            let path = format!("assets/{}", level.level_path());
+    for y in 0..grid.size().y {
+        for x in 0..grid.size().x {
+            let terrain = grid.terrain_at(uvec2(x, y));
+            if terrain == Terrain::Floor {
+                continue;
+            }
 
-            let mut writer = BufWriter::new(File::create(path).unwrap());
-            serde_json::to_writer(&mut writer, &state).unwrap();
-            writer.flush().unwrap();
+            match areas.last_mut() {
+                Some(area)
+                    if area.terrain == terrain
+                        && area.rect.max.y == y
+                        && area.rect.max.x + 1 == x =>
+                {
+                    area.rect.max.x = x;
+                }
+                _ => areas.push(TerrainArea {
+                    terrain,
+                    rect: URect::new(x, y, x, y),
+                }),
+            }
         }
     }
+
+    areas
 }
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+// what a cell of the grid is made of, under whatever is placed on it
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Terrain {
+    #[default]
+    Floor,
+    // nothing can be built on it
+    Wall,
+    // packets crossing it move slower
+    Slow,
+}
+
+impl Terrain {
+    pub fn sprite_path(&self) -> Option<&'static str> {
+        match self {
+            Terrain::Floor => None,
+            Terrain::Wall => Some("wall.png"),
+            Terrain::Slow => Some("slow.png"),
+        }
+    }
+
+    pub fn buildable(&self) -> bool {
+        *self != Terrain::Wall
+    }
+
+    // multiplies the speed of packets on it
+    pub fn speed(&self) -> f32 {
+        match self {
+            Terrain::Slow => 0.5,
+            _ => 1.,
+        }
+    }
+}
+
+// terrain covering a rectangle of cells in a grid file
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct TerrainArea {
+    pub terrain: Terrain,
+    pub rect: URect,
+}
+
+// a pre-placed item the player can use but can't sell or upgrade
+#[derive(Component)]
+pub struct Locked;
@@ -0,0 +1,287 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{
+    math::uvec2,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    grid::Grid,
+    items::cables::{Cable, CableDirection},
+    shop::shop_items::ItemType,
+};
+
+// what a node of the network does with the packets reaching it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NodeKind {
+    Pc,
+    EnemyPc,
+    Router,
+    Switch,
+    Server,
+}
+
+impl NodeKind {
+    pub fn from_item(item_type: ItemType) -> Option<Self> {
+        match item_type {
+            ItemType::PC | ItemType::CriticalPC | ItemType::DataCenter => Some(NodeKind::Pc),
+            ItemType::EnemyPC => Some(NodeKind::EnemyPc),
+            ItemType::Router => Some(NodeKind::Router),
+            ItemType::Switch => Some(NodeKind::Switch),
+            ItemType::Server => Some(NodeKind::Server),
+            _ => None,
+        }
+    }
+
+    // packets go through it instead of stopping there
+    pub fn relays(&self) -> bool {
+        matches!(self, NodeKind::Router | NodeKind::Switch | NodeKind::Server)
+    }
+}
+
+// a cable joining two nodes
+#[derive(Clone, Debug)]
+pub struct Link {
+    pub cable: Entity,
+    pub ends: [Entity; 2],
+    // cells of cable between the two nodes
+    pub length: u32,
+    pub dir: CableDirection,
+}
+
+impl Link {
+    pub fn other(&self, node: Entity) -> Entity {
+        match self.ends[0] == node {
+            true => self.ends[1],
+            false => self.ends[0],
+        }
+    }
+}
+
+// a way through the network, from its first node to its last
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkPath {
+    pub nodes: Vec<Entity>,
+    pub cables: Vec<Entity>,
+    pub length: u32,
+}
+
+// the network on the grid, rebuilt whenever something is placed or removed
+#[derive(Resource, Default)]
+pub struct Topology {
+    nodes: HashMap<Entity, NodeKind>,
+    links: Vec<Link>,
+    // links touching each node, as indices into `links`
+    adjacent: HashMap<Entity, Vec<usize>>,
+}
+
+impl Topology {
+    pub fn build(grid: &Grid, items: &Query<(&ItemType, Option<&Cable>)>) -> Self {
+        let mut topology = Topology::default();
+        let mut cables = Vec::new();
+
+        for (entity, _) in grid.entities() {
+            let Ok((item_type, cable)) = items.get(entity) else {
+                continue;
+            };
+            if let Some(cable) = cable {
+                cables.push((entity, cable));
+            } else if let Some(kind) = NodeKind::from_item(*item_type) {
+                topology.nodes.insert(entity, kind);
+            }
+        }
+
+        for (entity, cable) in cables {
+            let rect = cable.rect;
+            //the cells right before and after the cable
+            let (start, end, length) = match cable.dir {
+                CableDirection::Horizontal => (
+                    rect.min.x.checked_sub(1).map(|x| uvec2(x, rect.min.y)),
+                    uvec2(rect.max.x + 1, rect.min.y),
+                    rect.width() + 1,
+                ),
+                CableDirection::Vertical => (
+                    rect.min.y.checked_sub(1).map(|y| uvec2(rect.min.x, y)),
+                    uvec2(rect.min.x, rect.max.y + 1),
+                    rect.height() + 1,
+                ),
+            };
+
+            let node_at = |cell: UVec2| {
+                grid.contains(cell)
+                    .then(|| grid.grid[cell.x as usize][cell.y as usize])
+                    .flatten()
+                    .filter(|e| topology.nodes.contains_key(e))
+            };
+            let (Some(a), Some(b)) = (start.and_then(node_at), node_at(end)) else {
+                continue;
+            };
+
+            topology
+                .adjacent
+                .entry(a)
+                .or_default()
+                .push(topology.links.len());
+            topology
+                .adjacent
+                .entry(b)
+                .or_default()
+                .push(topology.links.len());
+            topology.links.push(Link {
+                cable: entity,
+                ends: [a, b],
+                length,
+                dir: cable.dir,
+            });
+        }
+
+        topology
+    }
+
+    pub fn node(&self, entity: Entity) -> Option<NodeKind> {
+        self.nodes.get(&entity).copied()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (Entity, NodeKind)> + '_ {
+        self.nodes.iter().map(|(entity, kind)| (*entity, *kind))
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn links_of(&self, node: Entity) -> impl Iterator<Item = &Link> {
+        self.adjacent
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .map(|i| &self.links[*i])
+    }
+
+    // packets can leave `from` and go on from `node`
+    fn passes(&self, from: Entity, node: Entity) -> bool {
+        node == from || self.node(node).is_some_and(|kind| kind.relays())
+    }
+
+    //every node a packet leaving `from` can get to
+    pub fn reachable(&self, from: Entity) -> HashSet<Entity> {
+        let mut seen = HashSet::from_iter([from]);
+        let mut queue = vec![from];
+
+        while let Some(node) = queue.pop() {
+            if !self.passes(from, node) {
+                continue;
+            }
+            for link in self.links_of(node) {
+                let next = link.other(node);
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    //the path with the least cable from `from` to `to`
+    pub fn shortest_path(&self, from: Entity, to: Entity) -> Option<NetworkPath> {
+        let mut best: HashMap<Entity, (u32, Option<&Link>)> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, from))]);
+        best.insert(from, (0, None));
+
+        while let Some(Reverse((length, node))) = queue.pop() {
+            if node == to {
+                break;
+            }
+            if length > best[&node].0 || !self.passes(from, node) {
+                continue;
+            }
+            for link in self.links_of(node) {
+                let next = link.other(node);
+                let next_length = length + link.length;
+                if best.get(&next).is_none_or(|(l, _)| next_length < *l) {
+                    best.insert(next, (next_length, Some(link)));
+                    queue.push(Reverse((next_length, next)));
+                }
+            }
+        }
+
+        let (length, _) = *best.get(&to)?;
+        let mut path = NetworkPath {
+            nodes: vec![to],
+            cables: Vec::new(),
+            length,
+        };
+        let mut node = to;
+        while let Some((_, Some(link))) = best.get(&node) {
+            node = link.other(node);
+            path.nodes.push(node);
+            path.cables.push(link.cable);
+        }
+        path.nodes.reverse();
+        path.cables.reverse();
+        Some(path)
+    }
+
+    //every way without loops from each enemy PC to each PC
+    pub fn enemy_paths(&self) -> Vec<NetworkPath> {
+        let mut paths = Vec::new();
+
+        for (enemy, _) in self.nodes().filter(|(_, k)| *k == NodeKind::EnemyPc) {
+            let mut path = NetworkPath {
+                nodes: vec![enemy],
+                cables: Vec::new(),
+                length: 0,
+            };
+            self.walk(&mut path, &mut paths);
+        }
+        paths
+    }
+
+    fn walk(&self, path: &mut NetworkPath, paths: &mut Vec<NetworkPath>) {
+        let node = *path.nodes.last().unwrap();
+        if self.node(node) == Some(NodeKind::Pc) {
+            paths.push(path.clone());
+            return;
+        }
+        if !self.passes(path.nodes[0], node) {
+            return;
+        }
+
+        for link in self.links_of(node) {
+            let next = link.other(node);
+            if path.nodes.contains(&next) {
+                continue;
+            }
+            path.nodes.push(next);
+            path.cables.push(link.cable);
+            path.length += link.length;
+            self.walk(path, paths);
+            path.nodes.pop();
+            path.cables.pop();
+            path.length -= link.length;
+        }
+    }
+}
+
+pub struct TopologyPlugin;
+
+impl Plugin for TopologyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Topology>();
+        app.add_systems(Update, update_topology);
+    }
+}
+
+//rebuilds the network once the grid or what's on it changed
+pub fn update_topology(
+    grid: Res<Grid>,
+    items: Query<(&ItemType, Option<&Cable>)>,
+    added: Query<(), Added<ItemType>>,
+    mut topology: ResMut<Topology>,
+) {
+    if !grid.is_changed() && added.is_empty() {
+        return;
+    }
+    *topology = Topology::build(&grid, &items);
+}
@@ -1,17 +1,57 @@
 use bevy::prelude::*;
 
-use crate::game::{GameStates, InGame};
+use crate::{
+    camera::SPRITE_SIZE,
+    game::{GameStates, InGame},
+    items::pcs::{Critical, Destroyed, PC},
+    levels::{LossRule, WaveManager},
+};
 
+pub const PC_HEALTH: i32 = 100;
+pub const CRITICAL_PC_HEALTH: i32 = 200;
+pub const DATA_CENTER_HEALTH: i32 = 300;
+
+// health left over all protected PCs
 #[derive(Resource)]
 pub struct Health {
     pub value: i32,
 }
+
+// health of a single PC, and whether a UPS is restoring it right now
+#[derive(Component)]
+pub struct PcHealth {
+    pub value: i32,
+    pub max: i32,
+    pub since_damage: f32,
+    pub regenerating: bool,
+}
+impl PcHealth {
+    pub fn new(max: i32) -> Self {
+        Self {
+            value: max,
+            max,
+            since_damage: 0.,
+            regenerating: false,
+        }
+    }
+}
+impl Default for PcHealth {
+    fn default() -> Self {
+        Self::new(PC_HEALTH)
+    }
+}
+
 #[derive(Component)]
 #[require(InGame)]
 pub struct HealthDisplay;
 
+// health shown above a PC
+#[derive(Component)]
+#[require(InGame)]
+pub struct PcHealthDisplay(pub Entity);
+
 #[derive(Event)]
-pub struct UpdateHealthEvent(pub i32);
+pub struct UpdateHealthEvent(pub Entity, pub i32);
 
 pub struct HealthPlugin;
 impl Plugin for HealthPlugin {
@@ -20,7 +60,14 @@ impl Plugin for HealthPlugin {
         app.add_systems(OnEnter(GameStates::InGame), init_health);
         app.add_systems(
             Update,
-            (update_health, defeat).run_if(in_state(GameStates::InGame)),
+            (
+                init_pc_health,
+                update_health,
+                update_pc_health_displays,
+                defeat,
+            )
+                .chain()
+                .run_if(in_state(GameStates::InGame)),
         );
         app.add_event::<UpdateHealthEvent>();
     }
@@ -30,7 +77,7 @@ impl Plugin for HealthPlugin {
 pub fn init_health(mut commands: Commands, health: Res<Health>) {
     commands.spawn((
         HealthDisplay,
-        Text::new(format!("Player Health: {} / 100", health.value)),
+        Text::new(format!("Player Health: {}", health.value)),
         TextFont {
             font_size: 14.0,
             ..Default::default()
@@ -42,25 +89,111 @@ pub fn init_health(mut commands: Commands, health: Res<Health>) {
     ));
 }
 
+//every PC gets its health shown above it
+fn init_pc_health(pcs: Query<Entity, Added<PC>>, mut commands: Commands) {
+    for pc in &pcs {
+        commands.spawn((
+            PcHealthDisplay(pc),
+            Text2d::new(""),
+            TextFont {
+                font_size: 7.0,
+                ..Default::default()
+            },
+            Transform::default(),
+        ));
+    }
+}
+
 //shows your health
 pub fn update_health(
     mut health: ResMut<Health>,
     mut event_update: EventReader<UpdateHealthEvent>,
+    mut pcs: Query<(&mut PcHealth, &mut Sprite), With<PC>>,
     mut display_health: Query<&mut Text, With<HealthDisplay>>,
+    time: Res<Time>,
+    mut commands: Commands,
 ) {
+    for (mut pc_health, _) in &mut pcs {
+        pc_health.since_damage += time.delta_secs();
+    }
+
     for ev in event_update.read() {
-        health.value += ev.0;
+        let Ok((mut pc_health, mut sprite)) = pcs.get_mut(ev.0) else {
+            continue;
+        };
+        if pc_health.value <= 0 {
+            continue;
+        }
+
+        pc_health.value = (pc_health.value + ev.1).min(pc_health.max);
+        if ev.1 < 0 {
+            pc_health.since_damage = 0.;
+        }
+
+        if pc_health.value <= 0 {
+            sprite.color = Color::srgb(0.3, 0.3, 0.3);
+            commands.entity(ev.0).insert(Destroyed);
+        }
     }
 
+    health.value = pcs.iter().map(|(h, _)| h.value.max(0)).sum();
+    let max: i32 = pcs.iter().map(|(h, _)| h.max).sum();
+    let regenerating = pcs.iter().any(|(h, _)| h.regenerating);
+
     let Ok(mut text) = display_health.get_single_mut() else {
         return;
     };
-    text.0 = format!("Player Health: {} / 100", health.value);
+    text.0 = format!("Player Health: {} / {}", health.value, max);
+    if regenerating {
+        text.0.push_str(" (regenerating)");
+    }
+}
+
+fn update_pc_health_displays(
+    mut displays: Query<(Entity, &PcHealthDisplay, &mut Text2d, &mut Transform)>,
+    pcs: Query<(&PcHealth, &Transform), Without<PcHealthDisplay>>,
+    mut commands: Commands,
+) {
+    for (display, pc, mut text, mut transform) in &mut displays {
+        let Ok((pc_health, pc_transform)) = pcs.get(pc.0) else {
+            commands.entity(display).despawn();
+            continue;
+        };
+
+        text.0 = match pc_health.regenerating {
+            true => format!("{} +", pc_health.value.max(0)),
+            false => format!("{}", pc_health.value.max(0)),
+        };
+        transform.translation = pc_transform.translation + Vec3::new(0., SPRITE_SIZE * 0.7, 3.);
+    }
 }
 
-//defeat screen if you end up with 0 health
-pub fn defeat(health: Res<Health>, mut next_state: ResMut<NextState<GameStates>>) {
-    if health.value <= 0 {
+//defeat screen once the PCs the level's loss rule cares about are destroyed
+pub fn defeat(
+    pcs: Query<(Has<Destroyed>, Has<Critical>), With<PC>>,
+    wave_manager: Res<WaveManager>,
+    mut next_state: ResMut<NextState<GameStates>>,
+) {
+    if pcs.is_empty() {
+        return;
+    }
+
+    let loss_rule = wave_manager
+        .level
+        .as_ref()
+        .map_or(LossRule::AnyPc, |l| l.loss_rule);
+
+    // losing a critical PC is always a defeat
+    let critical_lost = pcs
+        .iter()
+        .any(|(destroyed, critical)| destroyed && critical);
+
+    let lost = match loss_rule {
+        LossRule::AnyPc => pcs.iter().any(|(destroyed, _)| destroyed),
+        LossRule::AllPcs => pcs.iter().all(|(destroyed, _)| destroyed),
+    };
+
+    if lost || critical_lost {
         next_state.set(GameStates::DefeatScreen);
     }
 }
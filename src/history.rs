@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{EditorStates, GameStates},
+    grid::{
+        Grid,
+        cable_interaction::{CableSpawnMode, spawn_cable},
+        save_load::spawn_item,
+    },
+    items::{
+        cables::{Cable, CableDirection},
+        packets::EnemyPacket,
+        registry::ItemRegistry,
+        upgrades::{RestoreLevel, UpgradeLevel},
+    },
+    levels::WaveManager,
+    shop::{currency::Currency, shop_items::ItemType},
+};
+
+// an item as it was on the grid, enough to put it back
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub item_type: ItemType,
+    pub anchor: UVec2,
+    pub cable: Option<(URect, CableDirection)>,
+    pub level: u32,
+}
+
+impl Snapshot {
+    pub fn of(
+        entity: Entity,
+        grid: &Grid,
+        items: &Query<(&ItemType, Option<&Cable>, Option<&UpgradeLevel>)>,
+    ) -> Option<Self> {
+        let (item_type, cable, level) = items.get(entity).ok()?;
+        let rect = grid.bounds(entity)?;
+        Some(Snapshot {
+            item_type: *item_type,
+            anchor: rect.min,
+            cable: cable.map(|c| (rect, c.dir)),
+            level: level.map_or(0, |l| l.level),
+        })
+    }
+}
+
+// something the player did that can be taken back, with what it cost
+#[derive(Clone, Debug)]
+pub enum Action {
+    Place {
+        entity: Entity,
+        item: Snapshot,
+        price: i32,
+    },
+    Upgrade {
+        entity: Entity,
+        from: u32,
+        price: i32,
+    },
+    Remove {
+        entity: Entity,
+        item: Snapshot,
+        refund: i32,
+    },
+}
+
+impl Action {
+    fn entity_mut(&mut self) -> &mut Entity {
+        match self {
+            Action::Place { entity, .. }
+            | Action::Upgrade { entity, .. }
+            | Action::Remove { entity, .. } => entity,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct History {
+    done: Vec<Action>,
+    undone: Vec<Action>,
+}
+
+impl History {
+    //a new action, anything undone before it can't be redone anymore
+    pub fn record(&mut self, action: Action) {
+        self.done.push(action);
+        self.undone.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    //an item put back on the grid is a new entity, the actions about the old one follow it
+    fn remap(&mut self, old: Entity, new: Entity) {
+        for action in self.done.iter_mut().chain(self.undone.iter_mut()) {
+            let entity = action.entity_mut();
+            if *entity == old {
+                *entity = new;
+            }
+        }
+    }
+}
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>();
+        app.add_systems(
+            Update,
+            (
+                forget_during_waves.run_if(in_state(EditorStates::Playing).and(wave_running)),
+                undo_redo.run_if(in_state(EditorStates::Editing).or(not(wave_running))),
+            )
+                .chain()
+                .run_if(in_state(GameStates::InGame)),
+        );
+        app.add_systems(OnExit(GameStates::InGame), clear_history);
+    }
+}
+
+fn clear_history(mut history: ResMut<History>) {
+    history.clear();
+}
+
+//a wave is being sent or its packets are still on the cables
+fn wave_running(wave_manager: Res<WaveManager>, enemies: Query<(), With<EnemyPacket>>) -> bool {
+    wave_manager.sending() || !enemies.is_empty()
+}
+
+//while playing only what was done since the last wave ended can be undone, waves lock it in
+fn forget_during_waves(mut history: ResMut<History>) {
+    if !history.done.is_empty() || !history.undone.is_empty() {
+        history.clear();
+    }
+}
+
+//Ctrl+Z takes the last action back, Ctrl+Y does it again
+fn undo_redo(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<History>,
+    mut grid: ResMut<Grid>,
+    mut currency: ResMut<Currency>,
+    items: Query<(&ItemType, Option<&Cable>, Option<&UpgradeLevel>)>,
+    registry: Res<ItemRegistry>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let mut board = Board {
+        grid: &mut grid,
+        registry: &registry,
+        asset_server: &asset_server,
+        commands: &mut commands,
+    };
+
+    if keys.just_pressed(KeyCode::KeyZ) {
+        let Some(mut action) = history.done.pop() else {
+            return;
+        };
+        //None when the item isn't there anymore, the action is dropped then
+        let undone = match &mut action {
+            Action::Place { entity, price, .. } => board.take_off(*entity).then(|| {
+                currency.value += *price;
+                true
+            }),
+            Action::Upgrade {
+                entity,
+                from,
+                price,
+            } => items.contains(*entity).then(|| {
+                //the item keeps what it holds, only its upgrades go back
+                board.commands.entity(*entity).insert(RestoreLevel(*from));
+                currency.value += *price;
+                true
+            }),
+            Action::Remove {
+                entity,
+                item,
+                refund,
+            } => {
+                let new = (currency.value >= *refund)
+                    .then(|| board.put_back(item))
+                    .flatten();
+                if let Some(new) = new {
+                    history.remap(*entity, new);
+                    *entity = new;
+                    currency.value -= *refund;
+                }
+                Some(new.is_some())
+            }
+        };
+        match undone {
+            Some(true) => history.undone.push(action),
+            Some(false) => history.done.push(action),
+            None => {}
+        }
+    } else if keys.just_pressed(KeyCode::KeyY) {
+        let Some(mut action) = history.undone.pop() else {
+            return;
+        };
+        let done = match &mut action {
+            Action::Place {
+                entity,
+                item,
+                price,
+            } => {
+                let new = (currency.value >= *price)
+                    .then(|| board.put_back(item))
+                    .flatten();
+                if let Some(new) = new {
+                    history.remap(*entity, new);
+                    *entity = new;
+                    currency.value -= *price;
+                }
+                new.is_some()
+            }
+            Action::Upgrade {
+                entity,
+                from,
+                price,
+            } => {
+                let done = currency.value >= *price && items.contains(*entity);
+                if done {
+                    board
+                        .commands
+                        .entity(*entity)
+                        .insert(RestoreLevel(*from + 1));
+                    currency.value -= *price;
+                }
+                done
+            }
+            Action::Remove { entity, refund, .. } => {
+                let done = board.take_off(*entity);
+                if done {
+                    currency.value += *refund;
+                }
+                done
+            }
+        };
+        match done {
+            true => history.done.push(action),
+            false => history.undone.push(action),
+        }
+    }
+}
+
+// what's needed to take items off the grid and put them back
+struct Board<'a, 'w, 's> {
+    grid: &'a mut Grid,
+    registry: &'a ItemRegistry,
+    asset_server: &'a AssetServer,
+    commands: &'a mut Commands<'w, 's>,
+}
+
+impl Board<'_, '_, '_> {
+    fn take_off(&mut self, entity: Entity) -> bool {
+        if self.grid.cells_of(entity).is_empty() {
+            return false;
+        }
+        self.grid.clear_entity(entity);
+        self.commands.entity(entity).despawn_recursive();
+        true
+    }
+
+    fn put_back(&mut self, item: &Snapshot) -> Option<Entity> {
+        let entity = match item.cable {
+            Some((rect, dir)) => spawn_cable(
+                rect,
+                self.commands,
+                self.asset_server,
+                CableSpawnMode::Raw,
+                self.grid,
+                dir,
+            )?,
+            None => {
+                let footprint = self.registry.get(item.item_type).footprint;
+                if !self.grid.footprint_free(item.anchor, footprint) {
+                    return None;
+                }
+                spawn_item(
+                    item.anchor,
+                    item.item_type,
+                    self.registry,
+                    self.grid,
+                    self.asset_server,
+                    self.commands,
+                )
+            }
+        };
+        if item.level > 0 {
+            self.commands
+                .entity(entity)
+                .insert(RestoreLevel(item.level));
+        }
+        Some(entity)
+    }
+}
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::SPRITE_SIZE,
+    game::{EditorStates, GameStates, InGame},
+    grid::Grid,
+    shop::shop_items::ItemType,
+};
+
+use super::{
+    cables::Cable,
+    packets::{EnemyPacket, PacketDamageEvent},
+    registry::{ItemInfo, RegisterItem},
+};
+
+const SCAN_DAMAGE: i32 = 2;
+const PULSE_TIME: f32 = 0.3;
+
+#[derive(Component)]
+pub struct ScanRate(pub Timer);
+impl Default for ScanRate {
+    fn default() -> Self {
+        ScanRate(Timer::from_seconds(1.5, TimerMode::Repeating))
+    }
+}
+
+#[derive(Component)]
+#[require(InGame, ScanRate)]
+pub struct Antivirus;
+
+#[derive(Component)]
+#[require(InGame)]
+pub struct ScanPulse(Timer);
+
+pub struct AntivirusPlugin;
+
+impl Plugin for AntivirusPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::Antivirus,
+            ItemInfo {
+                name: "Antivirus",
+                sprite: "antivirus.png",
+                price: 40,
+                footprint: UVec2::ONE,
+                key: "Antivirus",
+                connectable: false,
+                shop_slot: Some(5),
+                spawn: |entity| {
+                    entity.insert(Antivirus);
+                },
+            },
+        );
+        app.add_systems(
+            Update,
+            (
+                scan_cables.run_if(in_state(EditorStates::Playing)),
+                animate_pulses,
+            )
+                .run_if(in_state(GameStates::InGame)),
+        );
+    }
+}
+
+//damages every enemy packet on the cables right next to the antivirus
+fn scan_cables(
+    mut scanners: Query<(&Transform, &mut ScanRate), With<Antivirus>>,
+    enemy_packets: Query<(Entity, &Transform), (With<EnemyPacket>, Without<Antivirus>)>,
+    cables: Query<&Cable>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+    mut damage_event: EventWriter<PacketDamageEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    for (scanner_pos, mut scan_rate) in &mut scanners {
+        if !scan_rate.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let Some(scanner_cell) = grid.world_to_grid(scanner_pos.translation.truncate()) else {
+            continue;
+        };
+
+        for (packet, packet_pos) in &enemy_packets {
+            let packet_pos = packet_pos.translation.truncate();
+            let Some(packet_cell) = grid.world_to_grid(packet_pos) else {
+                continue;
+            };
+
+            // orthogonally adjacent means exactly one cell away
+            if packet_cell.x.abs_diff(scanner_cell.x) + packet_cell.y.abs_diff(scanner_cell.y) != 1
+            {
+                continue;
+            }
+            if !grid
+                .get_element(packet_pos)
+                .is_some_and(|e| cables.contains(e))
+            {
+                continue;
+            }
+
+            damage_event.send(PacketDamageEvent {
+                target: packet,
+                damage: SCAN_DAMAGE,
+            });
+        }
+
+        let mut position = scanner_pos.translation;
+        position.z += 1.;
+
+        commands.spawn((
+            ScanPulse(Timer::from_seconds(PULSE_TIME, TimerMode::Once)),
+            Transform::from_translation(position),
+            MeshMaterial2d(materials.add(Color::Srgba(Srgba::new(0.2, 1., 0.4, 0.3)))),
+            Mesh2d(meshes.add(Circle::new(SPRITE_SIZE))),
+        ));
+    }
+}
+
+//the pulse grows over the scanned cells and fades out
+fn animate_pulses(
+    mut pulses: Query<(
+        Entity,
+        &mut ScanPulse,
+        &mut Transform,
+        &MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut pulse, mut transform, material) in &mut pulses {
+        if pulse.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let progress = pulse.0.fraction();
+        transform.scale = Vec3::splat(0.5 + progress);
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color.set_alpha(0.3 * (1. - progress));
+        }
+    }
+}
@@ -1,15 +1,22 @@
-use crate::{camera::SPRITE_SIZE, game::InGame, grid::Grid};
+use crate::{
+    balance::Balance, camera::SPRITE_SIZE, game::InGame, grid::Grid, shop::shop_items::ItemType,
+};
 use bevy::{math::vec2, prelude::*};
 
-use super::packets::Packet;
+use super::{
+    packets::Packet,
+    registry::{ItemInfo, RegisterItem},
+};
 
 #[derive(Component)]
 #[require(InGame)]
 pub struct Cable {
     pub dir: CableDirection,
+    // cells the cable covers
+    pub rect: URect,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Asset, TypePath, Copy, Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Asset, TypePath, Copy, Clone, Debug, PartialEq)]
 pub enum CableDirection {
     Vertical,
     Horizontal,
@@ -19,6 +26,20 @@ pub struct CablesPlugin;
 
 impl Plugin for CablesPlugin {
     fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::Cable,
+            ItemInfo {
+                name: "Cable",
+                sprite: "cable.png",
+                price: 1, //1 credit per square
+                footprint: UVec2::ONE,
+                key: "Cable",
+                connectable: false,
+                shop_slot: Some(2),
+                // cables are laid out cell by cell through spawn_cable
+                spawn: |_| {},
+            },
+        );
         app.add_systems(Update, move_packets);
     }
 }
@@ -29,25 +50,35 @@ fn move_packets(
     mut packets: Query<(Entity, &mut Transform, &Packet)>,
     cables: Query<&Cable>,
     grid: ResMut<Grid>,
+    balance: Res<Balance>,
     mut commands: Commands,
 ) {
     for (packet_entity, mut pos, packet) in packets.iter_mut() {
         let entity = grid.get_element(pos.translation.truncate());
         if let Some(_) = entity.and_then(|e| cables.get(e).ok()) {
-            pos.translation += packet.dir.extend(0.) * packet.stats().speed * time.delta_secs();
+            //slow ground holds packets back
+            let speed =
+                packet.stats(&balance).speed * grid.terrain(pos.translation.truncate()).speed();
+            pos.translation += packet.dir.extend(0.) * speed * time.delta_secs();
         } else {
             commands.entity(packet_entity).try_despawn();
         }
     }
 }
 
-const ADJ_SPACE: [Vec2; 4] = [vec2(-1., 0.), vec2(0., -1.), vec2(1., 0.), vec2(0., 1.)];
+//follows a cable from `start` in direction `dir`, returns the first position that isn't on a cable
+pub fn cable_end(start: Vec2, dir: Vec2, cables: &Query<&Cable>, grid: &Grid) -> Vec2 {
+    let mut pos = start;
+    while grid.get_element(pos).is_some_and(|e| cables.contains(e)) {
+        pos += dir * SPRITE_SIZE;
+    }
+    pos
+}
+
+// left, down, right, up
+pub const ADJ_SPACE: [Vec2; 4] = [vec2(-1., 0.), vec2(0., -1.), vec2(1., 0.), vec2(0., 1.)];
 
-pub fn get_adj_cables(
-    start_pos: Vec2,
-    cables: &Query<&Cable>,
-    grid: &ResMut<Grid>,
-) -> Vec<(Vec2, Vec2)> {
+pub fn get_adj_cables(start_pos: Vec2, cables: &Query<&Cable>, grid: &Grid) -> Vec<(Vec2, Vec2)> {
     let mut res: Vec<(Vec2, Vec2)> = Vec::new();
 
     let pos = grid.world_to_grid(start_pos).expect("bad item position");
@@ -1,16 +1,18 @@
-use std::{cmp, fs::File, io::Write};
-
 use crate::{
+    balance::Balance,
     camera::SPRITE_SIZE,
-    game::{GameLevels, GameStates, HIGHEST_LEVEL_PATH, HighestLevel, InGame},
+    game::{EditorStates, GameLevels, GameStates, InGame},
     grid::Grid,
     levels::{Level, WaveManager, advance_level, get_level},
+    savegame::ResumeGame,
+    shop::shop_items::ItemType,
 };
 use bevy::prelude::*;
 
 use super::{
     cables::{Cable, get_adj_cables},
     packets::{EnemyPacket, Packet},
+    registry::{ItemInfo, RegisterItem},
 };
 
 #[derive(Component)]
@@ -20,17 +22,38 @@ pub struct EnemyPCPlugin;
 
 impl Plugin for EnemyPCPlugin {
     fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::EnemyPC,
+            ItemInfo {
+                name: "Enemy PC",
+                sprite: "enemy_pc.png",
+                price: 0,
+                footprint: UVec2::ONE,
+                key: "EnemyPC",
+                connectable: true,
+                shop_slot: None,
+                spawn: |entity| {
+                    entity.insert(EnemyPC);
+                },
+            },
+        );
         app.insert_resource(WaveManager::default());
-        app.add_systems(OnEnter(GameStates::InGame), load_level);
-        app.add_systems(Update, create_packets.run_if(in_state(GameStates::InGame)));
+        app.add_systems(
+            OnEnter(GameStates::InGame),
+            load_level.run_if(not(resource_exists::<ResumeGame>)),
+        );
+        app.add_systems(
+            Update,
+            create_packets
+                .run_if(in_state(GameStates::InGame))
+                .run_if(in_state(EditorStates::Playing)),
+        );
     }
 }
 
 fn load_level(mut wave_manager: ResMut<WaveManager>, state: Res<State<GameLevels>>) {
-    *wave_manager = WaveManager::default();
     let lvl: Level = get_level((**state).clone());
-    wave_manager.level = Some(lvl.clone());
-    wave_manager.timer = (&lvl).get_timer(0, 0);
+    *wave_manager = WaveManager::new(lvl);
 }
 
 //sends packets to your pc
@@ -44,8 +67,8 @@ fn create_packets(
     mut wave_manager: ResMut<WaveManager>,
     enemy_packets: Query<&EnemyPacket>,
     state: Res<State<GameLevels>>,
-    mut highest: ResMut<HighestLevel>,
     mut next_state: ResMut<NextState<GameStates>>,
+    balance: Res<Balance>,
 ) {
     let all_enemies_killed = enemy_packets.is_empty();
 
@@ -56,14 +79,7 @@ fn create_packets(
         *state == GameLevels::Sandbox,
     ) else {
         if all_enemies_killed && !wave_manager.valid() {
-            let val: u8 = u8::from(**state);
-            highest.highest = cmp::max(val, highest.highest.into()).into();
-
-            let Ok(mut file) = File::create(HIGHEST_LEVEL_PATH) else {
-                return;
-            };
-            file.write(&[Into::<u8>::into(highest.highest)]).ok();
-
+            // the profile keeps the progress once the game is left
             next_state.set(GameStates::VictoryScreen);
         }
 
@@ -76,7 +92,7 @@ fn create_packets(
         for (cable_pos, adj_space) in cables {
             commands.spawn((
                 EnemyPacket,
-                Packet::new(adj_space, packet_type),
+                Packet::new(adj_space, packet_type, &balance),
                 Sprite::from_image(asset_server.load("enemy_packet.png")),
                 Transform::from_translation(
                     (cable_pos - adj_space * SPRITE_SIZE / 2.05).extend(2.),
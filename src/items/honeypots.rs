@@ -0,0 +1,198 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    game::{EditorStates, GameStates, InGame},
+    grid::Grid,
+    shake::Shake,
+    shop::{currency::UpdateCurrencyEvent, shop_items::ItemType},
+};
+
+use super::{
+    cables::{Cable, cable_end, get_adj_cables},
+    items_ui::HoverDetails,
+    packets::{EnemyPacket, Packet},
+    registry::{ItemInfo, RegisterItem},
+    routers::Router,
+};
+
+pub const HONEYPOT_CAPACITY: usize = 5;
+// a rack is three honeypots stacked together
+pub const RACK_CAPACITY: usize = 3 * HONEYPOT_CAPACITY;
+// every DRAIN_RATE seconds the oldest held packet loses DRAIN_DAMAGE hp
+const DRAIN_RATE: f32 = 1.;
+const DRAIN_DAMAGE: i32 = 5;
+// credits for every packet destroyed inside a honeypot
+const BOUNTY: i32 = 2;
+
+// hp of the enemy packets caught by a honeypot
+#[derive(Component)]
+pub struct HeldPackets {
+    pub capacity: usize,
+    pub held: Vec<i32>,
+    timer: Timer,
+}
+impl HeldPackets {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            held: Vec::new(),
+            timer: Timer::from_seconds(DRAIN_RATE, TimerMode::Repeating),
+        }
+    }
+}
+impl Default for HeldPackets {
+    fn default() -> Self {
+        Self::new(HONEYPOT_CAPACITY)
+    }
+}
+
+#[derive(Component)]
+#[require(InGame, HeldPackets, HoverDetails)]
+pub struct Honeypot;
+
+pub struct HoneypotsPlugin;
+
+impl Plugin for HoneypotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::Honeypot,
+            ItemInfo {
+                name: "Honeypot",
+                sprite: "honeypot.png",
+                price: 35,
+                footprint: UVec2::ONE,
+                key: "Honeypot",
+                connectable: true,
+                shop_slot: Some(4),
+                spawn: |entity| {
+                    entity.insert(Honeypot);
+                },
+            },
+        );
+        app.register_item(
+            ItemType::Rack,
+            ItemInfo {
+                name: "Rack",
+                sprite: "rack.png",
+                price: 80,
+                footprint: UVec2::new(1, 3),
+                key: "Rack",
+                connectable: true,
+                shop_slot: Some(7),
+                spawn: |entity| {
+                    entity.insert((Honeypot, HeldPackets::new(RACK_CAPACITY)));
+                },
+            },
+        );
+        app.add_systems(
+            Update,
+            (
+                (capture_packets, drain_packets).run_if(in_state(EditorStates::Playing)),
+                update_details,
+            )
+                .chain()
+                .run_if(in_state(GameStates::InGame)),
+        );
+    }
+}
+
+//enemy packets reaching a honeypot get stuck in it, too many of them destroy it
+fn capture_packets(
+    enemy_packets: Query<(Entity, &Transform, &Packet), With<EnemyPacket>>,
+    mut honeypots: Query<&mut HeldPackets, With<Honeypot>>,
+    cameras: Query<(Entity, &Transform), With<Camera2d>>,
+    mut grid: ResMut<Grid>,
+    mut commands: Commands,
+) {
+    for (packet_entity, pos, packet) in &enemy_packets {
+        let Some(honeypot) = grid.get_element(pos.translation.truncate()) else {
+            continue;
+        };
+        let Ok(mut storage) = honeypots.get_mut(honeypot) else {
+            continue;
+        };
+
+        commands.entity(packet_entity).despawn();
+
+        // already overwhelmed this frame
+        if storage.held.len() > storage.capacity {
+            continue;
+        }
+        storage.held.push(packet.hp);
+
+        if storage.held.len() > storage.capacity {
+            grid.clear_entity(honeypot);
+            commands.entity(honeypot).despawn_recursive();
+
+            let Ok((camera, pos)) = cameras.get_single() else {
+                continue;
+            };
+            commands
+                .entity(camera)
+                .insert_if_new(Shake::new(10., 0.2, pos.translation));
+        }
+    }
+}
+
+//slowly destroys the held packets, paying a bounty for each
+fn drain_packets(
+    mut honeypots: Query<&mut HeldPackets>,
+    time: Res<Time>,
+    mut currency_writer: EventWriter<UpdateCurrencyEvent>,
+) {
+    for mut storage in &mut honeypots {
+        if storage.held.is_empty() {
+            continue;
+        }
+        if !storage.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        storage.held[0] -= DRAIN_DAMAGE;
+        if storage.held[0] <= 0 {
+            storage.held.remove(0);
+            currency_writer.send(UpdateCurrencyEvent(BOUNTY));
+        }
+    }
+}
+
+fn update_details(mut honeypots: Query<(&HeldPackets, &mut HoverDetails), Changed<HeldPackets>>) {
+    for (storage, mut details) in &mut honeypots {
+        details.0 = format!("Holding {} / {}", storage.held.len(), storage.capacity);
+    }
+}
+
+//whether following the cable from `start` in direction `dir` reaches a honeypot, possibly through other routers
+pub fn leads_to_honeypot(
+    start: Vec2,
+    dir: Vec2,
+    grid: &Grid,
+    cables: &Query<&Cable>,
+    routers: &Query<(), With<Router>>,
+    honeypots: &Query<(), With<Honeypot>>,
+) -> bool {
+    let mut visited: HashSet<Entity> = HashSet::new();
+    let mut to_visit = vec![(start, dir)];
+
+    while let Some((pos, dir)) = to_visit.pop() {
+        let pos = cable_end(pos, dir, cables, grid);
+
+        let Some(end) = grid.get_element(pos) else {
+            continue;
+        };
+        if honeypots.contains(end) {
+            return true;
+        }
+        if !routers.contains(end) || !visited.insert(end) {
+            continue;
+        }
+
+        for (cable_pos, adj_space) in get_adj_cables(pos, cables, grid) {
+            if adj_space != -dir {
+                to_visit.push((cable_pos, adj_space));
+            }
+        }
+    }
+
+    false
+}
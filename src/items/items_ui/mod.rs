@@ -2,7 +2,8 @@ use bevy::prelude::*;
 
 use crate::{
     game::GameStates,
-    items::upgrades::UpgradeLevel,
+    grid::terrain::Locked,
+    items::{registry::ItemRegistry, upgrades::UpgradeLevel},
     shop::shop_items::{ItemType, ShopPosition},
 };
 
@@ -22,6 +23,13 @@ pub struct HoverLevel;
 #[derive(Component)]
 pub struct HoverCost;
 
+#[derive(Component)]
+pub struct HoverDetailsText;
+
+// extra item specific info shown under the upgrade cost, kept up to date by each item
+#[derive(Component, Default)]
+pub struct HoverDetails(pub String);
+
 #[derive(Resource, Default)]
 pub struct HoveredItem(pub Option<Entity>);
 
@@ -36,23 +44,40 @@ impl Plugin for ItemsUIPlugin {
 }
 
 fn update_ui(
-    items: Query<(&ItemType, &GlobalTransform, Option<&UpgradeLevel>)>,
+    items: Query<(
+        &ItemType,
+        &GlobalTransform,
+        Option<&UpgradeLevel>,
+        Option<&HoverDetails>,
+        Has<Locked>,
+    )>,
     mut hover_window: Single<&mut Node, With<HoverWindow>>,
     mut hover_name: Single<&mut Text, With<HoverName>>,
     mut hover_level: Single<&mut Text, (With<HoverLevel>, Without<HoverName>)>,
     mut hover_cost: Single<&mut Text, (With<HoverCost>, Without<HoverName>, Without<HoverLevel>)>,
+    mut hover_details: Single<
+        &mut Text,
+        (
+            With<HoverDetailsText>,
+            Without<HoverName>,
+            Without<HoverLevel>,
+            Without<HoverCost>,
+        ),
+    >,
     camera: Single<(&Camera, &GlobalTransform)>,
     hovered_item: Res<HoveredItem>,
+    registry: Res<ItemRegistry>,
 ) {
     let Some(item_id) = hovered_item.0 else {
         hover_name.0 = String::new();
         hover_level.0 = String::new();
         hover_cost.0 = String::new();
+        hover_details.0 = String::new();
 
         return;
     };
 
-    let Ok((item_type, item_transform, upgrade_level)) = items.get(item_id) else {
+    let Ok((item_type, item_transform, upgrade_level, details, locked)) = items.get(item_id) else {
         return;
     };
 
@@ -65,9 +90,10 @@ fn update_ui(
     hover_window.top = Val::Px(pos.y + 10.);
     hover_window.left = Val::Px(pos.x + 10.);
 
-    hover_name.0 = item_type.name();
+    hover_name.0 = registry.get(*item_type).name.to_string();
 
     hover_level.0 = match upgrade_level {
+        None if locked => "Locked".to_string(),
         None => "Fully Upgraded".to_string(),
         Some(x) => format!("Level {}", x.level + 1),
     };
@@ -79,6 +105,8 @@ fn update_ui(
             Some(p) => format!("Upgrade Cost: {p}"),
         },
     };
+
+    hover_details.0 = details.map(|d| d.0.clone()).unwrap_or_default();
 }
 
 fn init_ui(mut commands: Commands) {
@@ -122,11 +150,21 @@ fn init_ui(mut commands: Commands) {
         ))
         .id();
 
+    let hover_details = commands
+        .spawn((
+            Node { ..default() },
+            Text::new("..."),
+            TextFont::from_font_size(15.),
+            HoverDetailsText,
+        ))
+        .id();
+
     commands
         .entity(hover_window)
         .add_child(hover_name)
         .add_child(hover_level)
-        .add_child(hover_cost);
+        .add_child(hover_cost)
+        .add_child(hover_details);
 }
 
 //managing the hovering of items in the grid
@@ -1,6 +1,8 @@
+use antivirus::AntivirusPlugin;
 use bevy::prelude::*;
 use cables::CablesPlugin;
 use enemy_pc::EnemyPCPlugin;
+use honeypots::HoneypotsPlugin;
 use packets::PacketsPlugin;
 use pcs::PcsPlugin;
 use projectiles::ProjectilePlugin;
@@ -8,19 +10,24 @@ use routers::RoutersPlugin;
 use servers::ServersPlugin;
 use switches::SwitchesPlugin;
 use upgrades::UpgradesPlugin;
+use ups::UpsPlugin;
 
 use crate::items::items_ui::ItemsUIPlugin;
 
+pub mod antivirus;
 pub mod cables;
 pub mod enemy_pc;
+pub mod honeypots;
 pub mod items_ui;
 pub mod packets;
 pub mod pcs;
 pub mod projectiles;
+pub mod registry;
 pub mod routers;
 pub mod servers;
 pub mod switches;
 pub mod upgrades;
+pub mod ups;
 
 pub struct ItemsPlugin;
 
@@ -38,6 +45,9 @@ impl Plugin for ItemsPlugin {
             UpgradesPlugin,
             PcsPlugin,
             ItemsUIPlugin,
+            HoneypotsPlugin,
+            AntivirusPlugin,
+            UpsPlugin,
         ));
     }
 }
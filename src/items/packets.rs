@@ -1,8 +1,15 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
-use crate::{game::InGame, shake::Shake};
+use crate::{
+    balance::Balance, camera::SPRITE_SIZE, game::InGame, grid::Grid, score::RunStats, shake::Shake,
+};
+
+use super::cables::{Cable, CableDirection};
+
+// player and enemy packets closer than this on the same cable run into each other
+const COLLISION_RANGE: f32 = SPRITE_SIZE / 4.;
 
 #[derive(Component)]
 struct HitTime {
@@ -18,35 +25,44 @@ pub struct Packet {
     pub dmg_multi: i32,
 }
 impl Packet {
-    pub fn new(dir: Vec2, packet_type: PacketType) -> Self {
-        let stats: PacketStats = packet_type.into();
+    pub fn new(dir: Vec2, packet_type: PacketType, balance: &Balance) -> Self {
         Self {
             dir,
             packet_type,
-            hp: stats.health,
+            hp: balance.packet_stats(packet_type).health,
             dmg_multi: 1,
         }
     }
 
-    pub fn stats(&self) -> PacketStats {
-        self.packet_type.into()
+    pub fn stats(&self, balance: &Balance) -> PacketStats {
+        balance.packet_stats(self.packet_type)
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PacketType {
     Basic,
     Mid,
     Advanced,
+    Heavy,
+}
+impl PacketType {
+    pub const ALL: [PacketType; 4] = [
+        PacketType::Basic,
+        PacketType::Mid,
+        PacketType::Advanced,
+        PacketType::Heavy,
+    ];
 }
 
 pub struct PacketStats {
     pub speed: f32,
     pub health: i32,
     pub damage: i32,
+    pub shot_multi: i32, // multiplies the damage of the projectile a switch shoots with this packet
 }
 
-//stats of all 3 types of packets
+//stats of all types of packets, before the balance file
 impl Into<PacketStats> for PacketType {
     fn into(self) -> PacketStats {
         match self {
@@ -54,16 +70,25 @@ impl Into<PacketStats> for PacketType {
                 speed: 10.,
                 health: 10,
                 damage: 5,
+                shot_multi: 1,
             },
             Self::Mid => PacketStats {
                 speed: 10.,
                 health: 15,
                 damage: 8,
+                shot_multi: 1,
             },
             Self::Advanced => PacketStats {
                 speed: 10.,
                 health: 25,
                 damage: 11,
+                shot_multi: 1,
+            },
+            Self::Heavy => PacketStats {
+                speed: 7.,
+                health: 20,
+                damage: 8,
+                shot_multi: 2,
             },
         }
     }
@@ -88,20 +113,112 @@ pub struct PacketsPlugin;
 impl Plugin for PacketsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PacketDamageEvent>();
-        app.add_systems(Update, (receive_damage, packet_fx, packet_end_hit).chain());
+        app.add_systems(
+            Update,
+            (collide_packets, receive_damage, packet_fx, packet_end_hit).chain(),
+        );
+    }
+}
+
+// a packet as seen along the axis of the cable it's on
+struct CablePacket {
+    entity: Entity,
+    pos: f32,
+    dir: f32,
+    hp: i32,
+}
+
+//player and enemy packets meeting head-on trade damage, the survivor keeps going
+fn collide_packets(
+    packets: Query<
+        (Entity, &Transform, &Packet, Has<PlayerPacket>),
+        Or<(With<PlayerPacket>, With<EnemyPacket>)>,
+    >,
+    cables: Query<&Cable>,
+    grid: Res<Grid>,
+    mut damage_event: EventWriter<PacketDamageEvent>,
+) {
+    // player and enemy packets grouped by the cable they're on
+    let mut on_cables: HashMap<Entity, (Vec<CablePacket>, Vec<CablePacket>)> = HashMap::new();
+
+    for (entity, transform, packet, is_player) in &packets {
+        let pos = transform.translation.truncate();
+        let Some(cable_entity) = grid.get_element(pos) else {
+            continue;
+        };
+        let Ok(cable) = cables.get(cable_entity) else {
+            continue;
+        };
+
+        let (pos, dir) = match cable.dir {
+            CableDirection::Horizontal => (pos.x, packet.dir.x),
+            CableDirection::Vertical => (pos.y, packet.dir.y),
+        };
+        let cable_packet = CablePacket {
+            entity,
+            pos,
+            dir,
+            hp: packet.hp,
+        };
+
+        let lists = on_cables.entry(cable_entity).or_default();
+        if is_player {
+            lists.0.push(cable_packet);
+        } else {
+            lists.1.push(cable_packet);
+        }
+    }
+
+    for (mut players, mut enemies) in on_cables.into_values() {
+        if players.is_empty() || enemies.is_empty() {
+            continue;
+        }
+        enemies.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        for player in &mut players {
+            // only the enemies close enough along the cable need to be checked
+            let start = enemies.partition_point(|e| e.pos < player.pos - COLLISION_RANGE);
+
+            for enemy in enemies[start..].iter_mut() {
+                if enemy.pos > player.pos + COLLISION_RANGE || player.hp <= 0 {
+                    break;
+                }
+                if enemy.hp <= 0 || enemy.dir * player.dir >= 0. {
+                    continue;
+                }
+
+                let damage = player.hp.min(enemy.hp);
+                player.hp -= damage;
+                enemy.hp -= damage;
+
+                damage_event.send(PacketDamageEvent {
+                    target: player.entity,
+                    damage,
+                });
+                damage_event.send(PacketDamageEvent {
+                    target: enemy.entity,
+                    damage,
+                });
+            }
+        }
     }
 }
 
 fn receive_damage(
     mut event: EventReader<PacketDamageEvent>,
-    mut packets: Query<&mut Packet>,
+    mut packets: Query<(&mut Packet, Has<EnemyPacket>)>,
+    mut run: ResMut<RunStats>,
     mut commands: Commands,
 ) {
     for e in event.read() {
-        let Ok(mut packet) = packets.get_mut(e.target) else {
+        let Ok((mut packet, enemy)) = packets.get_mut(e.target) else {
             continue;
         };
 
+        // counted once, a packet can take more hits the frame it dies
+        if enemy && packet.hp > 0 && packet.hp <= e.damage {
+            run.packets_destroyed += 1;
+        }
         packet.hp -= e.damage;
         if packet.hp <= 0 {
             commands.entity(e.target).try_despawn();
@@ -134,7 +251,7 @@ fn packet_fx(
                 .entity(camera)
                 .insert_if_new(Shake::new(1., 0.1, pos.translation));
         } else {
-            *sprite = Sprite::from_image(asset_server.load("white_packet.png"));
+            sprite.image = asset_server.load("white_packet.png");
 
             commands.entity(e.target).insert(HitTime {
                 timer: Timer::new(Duration::from_secs_f32(0.2), TimerMode::Once),
@@ -144,15 +261,18 @@ fn packet_fx(
 }
 
 fn packet_end_hit(
-    mut packets: Query<(&mut Sprite, &mut HitTime), With<Packet>>,
+    mut packets: Query<(&mut Sprite, &mut HitTime, Option<&PlayerPacket>), With<Packet>>,
     time: Res<Time>,
     asset_server: Res<AssetServer>,
 ) {
-    for (mut sprite, mut hit_time) in packets.iter_mut() {
+    for (mut sprite, mut hit_time, is_player) in packets.iter_mut() {
         hit_time.timer.tick(time.delta());
 
         if hit_time.timer.finished() {
-            *sprite = Sprite::from_image(asset_server.load("enemy_packet.png"));
+            sprite.image = match is_player {
+                Some(_) => asset_server.load("player_packet.png"),
+                None => asset_server.load("enemy_packet.png"),
+            };
         }
     }
 }
@@ -1,45 +1,138 @@
 use bevy::prelude::*;
 
-use crate::{game::InGame, grid::Grid, health::UpdateHealthEvent, shake::Shake};
+use crate::{
+    balance::Balance,
+    camera::SPRITE_SIZE,
+    game::InGame,
+    grid::Grid,
+    health::{CRITICAL_PC_HEALTH, DATA_CENTER_HEALTH, PcHealth, UpdateHealthEvent},
+    levels::{DestroyedPcRule, WaveManager},
+    shake::Shake,
+    shop::shop_items::ItemType,
+};
 
-use super::packets::{EnemyPacket, Packet};
+use super::{
+    cables::{Cable, get_adj_cables},
+    packets::{EnemyPacket, Packet},
+    registry::{ItemInfo, RegisterItem},
+};
 
 #[derive(Component)]
-#[require(InGame)]
+#[require(InGame, PcHealth)]
 pub struct PC;
 
+// a PC the level can't afford to lose, it has more health but its fall is always a defeat
+#[derive(Component)]
+pub struct Critical;
+
+// a PC whose health ran out, it can't be cabled anymore
+#[derive(Component)]
+pub struct Destroyed;
+
 pub struct PcsPlugin;
 
 impl Plugin for PcsPlugin {
     fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::PC,
+            ItemInfo {
+                name: "PC",
+                sprite: "pc.png",
+                price: 0,
+                footprint: UVec2::ONE,
+                key: "PC",
+                connectable: true,
+                shop_slot: None,
+                spawn: |entity| {
+                    entity.insert(PC);
+                },
+            },
+        );
+        app.register_item(
+            ItemType::CriticalPC,
+            ItemInfo {
+                name: "Critical PC",
+                sprite: "critical_pc.png",
+                price: 0,
+                footprint: UVec2::ONE,
+                key: "CriticalPC",
+                connectable: true,
+                shop_slot: None,
+                spawn: |entity| {
+                    entity.insert((PC, Critical, PcHealth::new(CRITICAL_PC_HEALTH)));
+                },
+            },
+        );
+        app.register_item(
+            ItemType::DataCenter,
+            ItemInfo {
+                name: "Data Center",
+                sprite: "data_center.png",
+                price: 0,
+                footprint: UVec2::new(2, 2),
+                key: "DataCenter",
+                connectable: true,
+                shop_slot: None,
+                spawn: |entity| {
+                    entity.insert((PC, PcHealth::new(DATA_CENTER_HEALTH)));
+                },
+            },
+        );
         app.add_systems(Update, take_damage);
     }
 }
 
 //loses health when an enemy packet reaches it
 fn take_damage(
-    enemy_packets: Query<(Entity, &Transform, &Packet), With<EnemyPacket>>,
+    mut enemy_packets: Query<(Entity, &mut Transform, &mut Packet), With<EnemyPacket>>,
     mut commands: Commands,
-    pcs: Query<&PC>,
-    cameras: Query<(Entity, &Transform), With<Camera2d>>,
+    pcs: Query<Has<Destroyed>, With<PC>>,
+    cables: Query<&Cable>,
+    cameras: Query<(Entity, &Transform), (With<Camera2d>, Without<Packet>)>,
     grid: Res<Grid>,
+    wave_manager: Res<WaveManager>,
+    balance: Res<Balance>,
     mut update_health_writer: EventWriter<UpdateHealthEvent>,
 ) {
-    for (packet_entity, pos, packet) in &enemy_packets {
-        if let Some(_) = grid
+    for (packet_entity, mut pos, mut packet) in &mut enemy_packets {
+        let Some((pc, destroyed)) = grid
             .get_element(pos.translation.truncate())
-            .and_then(|e| pcs.get(e).ok())
-        {
-            update_health_writer.send(UpdateHealthEvent(-packet.stats().damage));
-            let Ok((camera, pos)) = cameras.get_single() else {
-                return;
-            };
-
-            commands
-                .entity(camera)
-                .insert_if_new(Shake::new(15., 0.2, pos.translation));
-
-            commands.entity(packet_entity).despawn();
+            .and_then(|e| pcs.get(e).ok().map(|d| (e, d)))
+        else {
+            continue;
+        };
+
+        if destroyed {
+            let rule = wave_manager
+                .level
+                .as_ref()
+                .map_or(DestroyedPcRule::Discard, |l| l.destroyed_pc);
+
+            //the packet goes through the remains of the PC, if it has somewhere to go
+            let next = get_adj_cables(pos.translation.truncate(), &cables, &grid)
+                .into_iter()
+                .find(|(_, adj_space)| *adj_space != -packet.dir);
+
+            match (rule, next) {
+                (DestroyedPcRule::Continue, Some((cable_pos, adj_space))) => {
+                    packet.dir = adj_space;
+                    pos.translation = (cable_pos - adj_space * SPRITE_SIZE / 2.05).extend(2.);
+                }
+                _ => {
+                    commands.entity(packet_entity).despawn();
+                }
+            }
+            continue;
         }
+
+        update_health_writer.send(UpdateHealthEvent(pc, -packet.stats(&balance).damage));
+        commands.entity(packet_entity).despawn();
+
+        let Ok((camera, pos)) = cameras.get_single() else {
+            continue;
+        };
+        commands
+            .entity(camera)
+            .insert_if_new(Shake::new(15., 0.2, pos.translation));
     }
 }
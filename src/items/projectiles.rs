@@ -1,34 +1,134 @@
 use bevy::prelude::*;
 
-use crate::shake::Shake;
+use crate::{balance::Balance, camera::SPRITE_SIZE, shake::Shake};
 
 use super::packets::{EnemyPacket, PacketDamageEvent};
 
 const COLLISION_RANGE: f32 = 1.;
+// piercing projectiles don't home in on anything, so they get a wider hitbox
+const PIERCE_COLLISION_RANGE: f32 = SPRITE_SIZE / 2.;
 
 #[derive(Component)]
 pub struct Projectile {
     pub target: Entity,
     pub projectile_type: ProjectileType,
     pub dmg_multi: i32,
+    dir: Vec3,
+    // damage scale, lowered on every chain jump
+    scale: f32,
+    jumps: u32,
+    // set once a piercing projectile hit its first target
+    straight: bool,
+    travelled: f32,
+    hit: Vec<Entity>,
 }
 
 impl Projectile {
-    pub fn stats(&self) -> ProjectileStats {
-        self.projectile_type.into()
+    pub fn new(target: Entity, projectile_type: ProjectileType, dmg_multi: i32) -> Self {
+        Self {
+            target,
+            projectile_type,
+            dmg_multi,
+            dir: Vec3::ZERO,
+            scale: 1.,
+            jumps: 0,
+            straight: false,
+            travelled: 0.,
+            hit: Vec::new(),
+        }
+    }
+
+    pub fn stats(&self, balance: &Balance) -> ProjectileStats {
+        balance.projectile_stats(self.projectile_type)
+    }
+
+    //the projectile as written in a save game, `index` finds saved enemy packets
+    pub fn save(&self, pos: Vec3, index: impl Fn(Entity) -> Option<usize>) -> SavedProjectile {
+        SavedProjectile {
+            pos,
+            target: index(self.target),
+            projectile_type: self.projectile_type,
+            dmg_multi: self.dmg_multi,
+            dir: self.dir,
+            scale: self.scale,
+            jumps: self.jumps,
+            straight: self.straight,
+            travelled: self.travelled,
+            hit: self.hit.iter().filter_map(|e| index(*e)).collect(),
+        }
+    }
+
+    //back from a save game, `packets` are the enemy packets spawned again in saved order
+    pub fn load(saved: &SavedProjectile, packets: &[Entity]) -> Self {
+        let packet = |i: usize| packets.get(i).copied();
+        Self {
+            target: saved.target.and_then(packet).unwrap_or(Entity::PLACEHOLDER),
+            projectile_type: saved.projectile_type,
+            dmg_multi: saved.dmg_multi,
+            dir: saved.dir,
+            scale: saved.scale,
+            jumps: saved.jumps,
+            straight: saved.straight,
+            travelled: saved.travelled,
+            hit: saved.hit.iter().filter_map(|i| packet(*i)).collect(),
+        }
+    }
+
+    pub fn damage(&self, balance: &Balance) -> i32 {
+        let damage = (self.stats(balance).damage * self.dmg_multi) as f32 * self.scale;
+        damage.round() as i32
     }
 }
 
+// a projectile in a save game, its target and what it hit are indices of saved enemy packets
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedProjectile {
+    pub pos: Vec3,
+    target: Option<usize>,
+    projectile_type: ProjectileType,
+    dmg_multi: i32,
+    dir: Vec3,
+    scale: f32,
+    jumps: u32,
+    straight: bool,
+    travelled: f32,
+    hit: Vec<usize>,
+}
+
 pub struct ProjectileStats {
     pub speed: f32,
     pub damage: i32,
+    pub behaviour: ProjectileBehaviour,
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProjectileBehaviour {
+    // damages only its target
+    Single,
+    // damages every enemy packet around the impact
+    Splash {
+        radius: f32,
+    },
+    // jumps to up to `jumps` more targets, each hit dealing `falloff` times the previous damage
+    Chain {
+        jumps: u32,
+        range: f32,
+        falloff: f32,
+    },
+    // keeps going in a straight line after the first hit, damaging everything it crosses
+    Piercing {
+        max_distance: f32,
+    },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Component, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProjectileType {
     Basic,
     Mid,
     Advanced,
+    Splash,
+    Chain,
+    Piercing,
 }
 
 impl Default for ProjectileType {
@@ -37,21 +137,47 @@ impl Default for ProjectileType {
     }
 }
 
-//types of projectiles
+//types of projectiles, before the balance file
 impl Into<ProjectileStats> for ProjectileType {
     fn into(self) -> ProjectileStats {
         match self {
             Self::Basic => ProjectileStats {
                 speed: 50.,
                 damage: 6,
+                behaviour: ProjectileBehaviour::Single,
             },
             Self::Mid => ProjectileStats {
                 speed: 60.,
                 damage: 10,
+                behaviour: ProjectileBehaviour::Single,
             },
             Self::Advanced => ProjectileStats {
                 speed: 100.,
                 damage: 15,
+                behaviour: ProjectileBehaviour::Single,
+            },
+            Self::Splash => ProjectileStats {
+                speed: 80.,
+                damage: 12,
+                behaviour: ProjectileBehaviour::Splash {
+                    radius: 1.5 * SPRITE_SIZE,
+                },
+            },
+            Self::Chain => ProjectileStats {
+                speed: 120.,
+                damage: 15,
+                behaviour: ProjectileBehaviour::Chain {
+                    jumps: 3,
+                    range: 4. * SPRITE_SIZE,
+                    falloff: 0.7,
+                },
+            },
+            Self::Piercing => ProjectileStats {
+                speed: 150.,
+                damage: 15,
+                behaviour: ProjectileBehaviour::Piercing {
+                    max_distance: 15. * SPRITE_SIZE,
+                },
             },
         }
     }
@@ -67,48 +193,129 @@ impl Plugin for ProjectilePlugin {
 
 //travelling to the enemy packet
 fn move_projectiles(
-    mut projectiles: Query<(&mut Transform, &Projectile, Entity), Without<EnemyPacket>>,
+    mut projectiles: Query<(&mut Transform, &mut Projectile, Entity), Without<EnemyPacket>>,
     enemy_packets: Query<&Transform, With<EnemyPacket>>,
     mut commands: Commands,
     time: Res<Time>,
+    balance: Res<Balance>,
 ) {
-    for (mut t_projectile, projectile, projectile_id) in &mut projectiles {
-        let Ok(t_target) = enemy_packets.get(projectile.target) else {
-            commands.entity(projectile_id).despawn();
-            continue;
-        };
+    for (mut t_projectile, mut projectile, projectile_id) in &mut projectiles {
+        let stats = projectile.stats(&balance);
 
-        let dir = (t_target.translation - t_projectile.translation).normalize();
-        t_projectile.translation += dir * projectile.stats().speed * time.delta_secs();
+        if projectile.straight {
+            //piercing projectiles fly until they run out of distance
+            if let ProjectileBehaviour::Piercing { max_distance } = stats.behaviour
+                && projectile.travelled >= max_distance
+            {
+                commands.entity(projectile_id).despawn();
+                continue;
+            }
+        } else {
+            let Ok(t_target) = enemy_packets.get(projectile.target) else {
+                commands.entity(projectile_id).despawn();
+                continue;
+            };
+            projectile.dir = (t_target.translation - t_projectile.translation).normalize_or_zero();
+        }
+
+        let step = stats.speed * time.delta_secs();
+        t_projectile.translation += projectile.dir * step;
+        projectile.travelled += step;
     }
 }
 
 //projectile hits enemy packet
 fn collide(
-    projectiles: Query<(Entity, &GlobalTransform, &Projectile)>,
+    mut projectiles: Query<(Entity, &GlobalTransform, &mut Projectile)>,
     enemy_packets: Query<(Entity, &GlobalTransform), With<EnemyPacket>>,
     mut damage_event: EventWriter<PacketDamageEvent>,
     cameras: Query<(Entity, &Transform), With<Camera2d>>,
+    balance: Res<Balance>,
     mut commands: Commands,
 ) {
-    for (projectile_id, t_projectile, projectile) in &projectiles {
+    for (projectile_id, t_projectile, mut projectile) in &mut projectiles {
+        let pos = t_projectile.translation().truncate();
+
+        //piercing projectiles damage everything along their way, once
+        if projectile.straight {
+            for (target, t_target) in &enemy_packets {
+                if projectile.hit.contains(&target)
+                    || t_target.translation().truncate().distance(pos) > PIERCE_COLLISION_RANGE
+                {
+                    continue;
+                }
+                damage_event.send(PacketDamageEvent {
+                    target,
+                    damage: projectile.damage(&balance),
+                });
+                projectile.hit.push(target);
+            }
+            continue;
+        }
+
         let Ok((target, t_target)) = enemy_packets.get(projectile.target) else {
             continue;
         };
 
-        if t_target.translation().distance(t_projectile.translation()) <= COLLISION_RANGE {
-            damage_event.send(PacketDamageEvent {
-                target,
-                damage: projectile.stats().damage * projectile.dmg_multi,
-            });
-            commands.entity(projectile_id).despawn();
+        if t_target.translation().distance(t_projectile.translation()) > COLLISION_RANGE {
+            continue;
+        }
 
-            let Ok((camera, pos)) = cameras.get_single() else {
-                return;
-            };
+        damage_event.send(PacketDamageEvent {
+            target,
+            damage: projectile.damage(&balance),
+        });
+        projectile.hit.push(target);
+
+        if let Ok((camera, pos)) = cameras.get_single() {
             commands
                 .entity(camera)
                 .insert_if_new(Shake::new(10., 0.02, pos.translation));
         }
+
+        match projectile.stats(&balance).behaviour {
+            ProjectileBehaviour::Single => {
+                commands.entity(projectile_id).despawn();
+            }
+            ProjectileBehaviour::Splash { radius } => {
+                for (other, t_other) in &enemy_packets {
+                    if other == target || t_other.translation().truncate().distance(pos) > radius {
+                        continue;
+                    }
+                    damage_event.send(PacketDamageEvent {
+                        target: other,
+                        damage: projectile.damage(&balance),
+                    });
+                }
+                commands.entity(projectile_id).despawn();
+            }
+            ProjectileBehaviour::Chain {
+                jumps,
+                range,
+                falloff,
+            } => {
+                let next = enemy_packets
+                    .iter()
+                    .filter(|(e, _)| !projectile.hit.contains(e))
+                    .map(|(e, t)| (e, t.translation().truncate().distance(pos)))
+                    .filter(|&(_, dist)| dist <= range)
+                    .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+
+                match next {
+                    Some((next, _)) if projectile.jumps < jumps => {
+                        projectile.target = next;
+                        projectile.jumps += 1;
+                        projectile.scale *= falloff;
+                    }
+                    _ => {
+                        commands.entity(projectile_id).despawn();
+                    }
+                }
+            }
+            ProjectileBehaviour::Piercing { .. } => {
+                projectile.straight = true;
+                projectile.dir = projectile.dir.with_z(0.).normalize_or_zero();
+            }
+        }
     }
 }
@@ -0,0 +1,91 @@
+use bevy::{ecs::system::EntityCommands, prelude::*, utils::HashMap};
+
+use crate::shop::shop_items::ItemType;
+
+// everything the game needs to know about a kind of item, registered once by the item's plugin
+pub struct ItemInfo {
+    pub name: &'static str,
+    pub sprite: &'static str,
+    pub price: u32,
+    // cells the item covers, its position in grid files is the bottom left one
+    pub footprint: UVec2,
+    // how the item is written in grid files
+    pub key: &'static str,
+    // whether cables can be connected to it
+    pub connectable: bool,
+    // position in the shop from left to right, None keeps it to the level editor
+    pub shop_slot: Option<u32>,
+    // inserts the components that make the item work
+    pub spawn: fn(&mut EntityCommands),
+}
+
+#[derive(Resource, Default)]
+pub struct ItemRegistry {
+    items: HashMap<ItemType, ItemInfo>,
+    // prices the items were registered with, before any balance file
+    default_prices: HashMap<ItemType, u32>,
+}
+
+impl ItemRegistry {
+    pub fn get(&self, item_type: ItemType) -> &ItemInfo {
+        self.items
+            .get(&item_type)
+            .unwrap_or_else(|| panic!("item type {item_type:?} was never registered"))
+    }
+
+    //finds the item type written as `key` in a grid file
+    pub fn from_key(&self, key: &str) -> Option<ItemType> {
+        self.items
+            .iter()
+            .find(|(_, info)| info.key == key)
+            .map(|(item_type, _)| *item_type)
+    }
+
+    //prices by item key, items left out go back to their registered price
+    pub fn apply_prices(&mut self, prices: &std::collections::HashMap<String, u32>) {
+        for (item_type, info) in &mut self.items {
+            info.price = prices
+                .get(info.key)
+                .copied()
+                .unwrap_or(self.default_prices[item_type]);
+        }
+    }
+
+    //item types sold in the shop, in shop order
+    pub fn shop_items(&self) -> Vec<ItemType> {
+        let mut items: Vec<(u32, ItemType)> = self
+            .items
+            .iter()
+            .filter_map(|(item_type, info)| info.shop_slot.map(|slot| (slot, *item_type)))
+            .collect();
+        items.sort_by_key(|(slot, _)| *slot);
+        items.into_iter().map(|(_, item_type)| item_type).collect()
+    }
+
+    //every item type, the shop ones first then the editor only ones by name
+    pub fn palette_items(&self) -> Vec<ItemType> {
+        let mut items = self.shop_items();
+        let mut editor_only: Vec<(&str, ItemType)> = self
+            .items
+            .iter()
+            .filter(|(_, info)| info.shop_slot.is_none())
+            .map(|(item_type, info)| (info.name, *item_type))
+            .collect();
+        editor_only.sort_by_key(|(name, _)| *name);
+        items.extend(editor_only.into_iter().map(|(_, item_type)| item_type));
+        items
+    }
+}
+
+pub trait RegisterItem {
+    fn register_item(&mut self, item_type: ItemType, info: ItemInfo) -> &mut Self;
+}
+
+impl RegisterItem for App {
+    fn register_item(&mut self, item_type: ItemType, info: ItemInfo) -> &mut Self {
+        let mut registry = self.world_mut().get_resource_or_init::<ItemRegistry>();
+        registry.default_prices.insert(item_type, info.price);
+        registry.items.insert(item_type, info);
+        self
+    }
+}
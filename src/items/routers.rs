@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 
-use crate::{camera::SPRITE_SIZE, game::InGame, grid::Grid, shake::Shake};
+use crate::{
+    camera::SPRITE_SIZE, game::InGame, grid::Grid, shake::Shake, shop::shop_items::ItemType,
+};
 
 use super::{
     cables::{Cable, get_adj_cables},
+    honeypots::{Honeypot, leads_to_honeypot},
     packets::{EnemyPacket, Packet, PlayerPacket},
+    registry::{ItemInfo, RegisterItem},
 };
 
 #[derive(Component, Default)]
@@ -18,6 +22,21 @@ pub struct RoutersPlugin;
 
 impl Plugin for RoutersPlugin {
     fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::Router,
+            ItemInfo {
+                name: "Router",
+                sprite: "router.png",
+                price: 20,
+                footprint: UVec2::ONE,
+                key: "Router",
+                connectable: true,
+                shop_slot: Some(0),
+                spawn: |entity| {
+                    entity.insert(Router);
+                },
+            },
+        );
         app.add_systems(Update, redirect_packets);
     }
 }
@@ -33,6 +52,8 @@ fn redirect_packets(
         Option<&EnemyPacket>,
     )>,
     routers: Query<(Entity, &DamageMultiplier, &Transform), Without<Packet>>,
+    router_cells: Query<(), With<Router>>,
+    honeypots: Query<(), With<Honeypot>>,
     cables: Query<&Cable>,
     grid: ResMut<Grid>,
     mut commands: Commands,
@@ -46,22 +67,44 @@ fn redirect_packets(
                 .entity(router)
                 .insert_if_new(Shake::new(2., 0.2, router_transf.translation));
 
-            let cables: Vec<(Vec2, Vec2)> =
+            let mut adj_cables: Vec<(Vec2, Vec2)> =
                 get_adj_cables(pos.translation.truncate(), &cables, &grid)
                     .into_iter()
                     .filter(|(_, adj_space)| adj_space * -1. != packet.dir)
                     .collect();
 
-            if cables.len() == 0 {
+            //enemy packets get lured towards honeypots whenever one is reachable
+            if is_enemy.is_some() {
+                let to_honeypots: Vec<(Vec2, Vec2)> = adj_cables
+                    .iter()
+                    .copied()
+                    .filter(|&(cable_pos, adj_space)| {
+                        leads_to_honeypot(
+                            cable_pos,
+                            adj_space,
+                            &grid,
+                            &cables,
+                            &router_cells,
+                            &honeypots,
+                        )
+                    })
+                    .collect();
+
+                if !to_honeypots.is_empty() {
+                    adj_cables = to_honeypots;
+                }
+            }
+
+            if adj_cables.is_empty() {
                 commands.entity(packet_entity).try_despawn();
                 continue;
             }
 
             packet.dmg_multi = r_dmg_multi.0;
 
-            for (index, &(cable_pos, adj_space)) in cables.iter().enumerate() {
+            for (index, &(cable_pos, adj_space)) in adj_cables.iter().enumerate() {
                 // move the last packet
-                if index == cables.len() - 1 {
+                if index == adj_cables.len() - 1 {
                     packet.dir = adj_space;
                     pos.translation = (cable_pos - adj_space * SPRITE_SIZE / 2.05).extend(2.);
                 }
@@ -1,61 +1,194 @@
 use bevy::prelude::*;
 
 use crate::{
+    balance::Balance,
     camera::SPRITE_SIZE,
-    game::{GameStates, InGame},
+    game::{EditorStates, GameStates, InGame},
     grid::Grid,
     shake::Shake,
+    shop::shop_items::ItemType,
 };
 
 use super::{
-    cables::{Cable, get_adj_cables},
+    cables::{ADJ_SPACE, Cable, get_adj_cables},
+    items_ui::{HoverDetails, HoveredItem},
     packets::{Packet, PacketType, PlayerPacket},
+    registry::{ItemInfo, RegisterItem},
+    upgrades::UpgradeLevel,
 };
 
+// packets fired in one burst, and the time between them
+const BURST_SIZE: u32 = 3;
+const BURST_GAP: f32 = 0.2;
+
+// keys that toggle the ports of the hovered server, in the same order as ADJ_SPACE
+const PORT_KEYS: [KeyCode; 4] = [
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowUp,
+];
+
+// seconds between packets before any upgrades
+pub const FIRE_RATE: f32 = 3.;
+
 #[derive(Component)]
 pub struct FireRate(pub Timer);
 impl Default for FireRate {
     fn default() -> Self {
-        FireRate(Timer::from_seconds(3., TimerMode::Repeating))
+        FireRate(Timer::from_seconds(FIRE_RATE, TimerMode::Repeating))
     }
 }
 
+// what a server sends out, configured by the player once unlocked through upgrades
+#[derive(Component)]
+pub struct ServerOutput {
+    pub ports: [bool; 4],
+    pub packet_type: PacketType,
+    pub burst: bool,
+    pub unlocked: ServerUnlocks,
+    pub burst_left: u32,
+    pub burst_timer: Timer,
+}
+impl Default for ServerOutput {
+    fn default() -> Self {
+        Self {
+            ports: [true; 4],
+            packet_type: PacketType::Basic,
+            burst: false,
+            unlocked: ServerUnlocks::default(),
+            burst_left: 0,
+            burst_timer: Timer::from_seconds(BURST_GAP, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ServerUnlocks {
+    pub ports: bool,
+    pub heavy: bool,
+    pub burst: bool,
+}
+
 #[derive(Component)]
-#[require(InGame, FireRate)]
+#[require(InGame, FireRate, ServerOutput, HoverDetails)]
 pub struct Server;
 
 pub struct ServersPlugin;
 
 impl Plugin for ServersPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, create_packets.run_if(in_state(GameStates::InGame)));
+        app.register_item(
+            ItemType::Server,
+            ItemInfo {
+                name: "Server",
+                sprite: "server.png",
+                price: 30,
+                footprint: UVec2::ONE,
+                key: "Server",
+                connectable: true,
+                shop_slot: Some(3),
+                spawn: |entity| {
+                    entity.insert(Server);
+                },
+            },
+        );
+        app.add_systems(
+            Update,
+            (
+                apply_fire_rate,
+                configure_servers,
+                create_packets.run_if(in_state(EditorStates::Playing)),
+                update_details,
+            )
+                .chain()
+                .run_if(in_state(GameStates::InGame)),
+        );
+    }
+}
+
+//servers that weren't upgraded yet fire at the rate from the balance file
+fn apply_fire_rate(
+    mut servers: Query<(Ref<Server>, &mut FireRate, Option<Ref<UpgradeLevel>>)>,
+    balance: Res<Balance>,
+) {
+    for (server, mut fire_rate, level) in &mut servers {
+        // undoing the first upgrade brings a server back to the base rate
+        let undone = level
+            .as_ref()
+            .is_some_and(|l| l.is_changed() && !l.is_added());
+        if !server.is_added() && !balance.is_changed() && !undone {
+            continue;
+        }
+        if level.is_some_and(|l| l.level > 0) {
+            continue;
+        }
+        let rate = balance.fire_rate.unwrap_or(FIRE_RATE);
+        fire_rate.0 = Timer::from_seconds(rate, TimerMode::Repeating);
     }
 }
 
 fn create_packets(
-    mut packet_senders: Query<(Entity, &Transform, &mut FireRate), With<Server>>,
+    mut packet_senders: Query<(Entity, &Transform, &mut FireRate, &mut ServerOutput), With<Server>>,
     cables: Query<&Cable>,
     grid: ResMut<Grid>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     time: Res<Time>,
+    balance: Res<Balance>,
 ) {
-    for (server_entity, packet_sender, mut fire_rate) in &mut packet_senders {
-        if !fire_rate.0.tick(time.delta()).just_finished() {
+    for (server_entity, packet_sender, mut fire_rate, mut output) in &mut packet_senders {
+        // burst bookkeeping isn't a change the hover info cares about
+        let output = output.bypass_change_detection();
+
+        let fire = if output.burst_left > 0 {
+            output.burst_timer.tick(time.delta()).just_finished()
+        } else if output.burst && output.unlocked.burst {
+            // a burst has the same average throughput, so the cooldown is BURST_SIZE times longer
+            let delta = time.delta() / BURST_SIZE;
+            if fire_rate.0.tick(delta).just_finished() {
+                output.burst_left = BURST_SIZE;
+                output.burst_timer.reset();
+            }
+            output.burst_left > 0
+        } else {
+            fire_rate.0.tick(time.delta()).just_finished()
+        };
+
+        if !fire {
             continue;
         }
-        commands
-            .entity(server_entity)
-            .insert_if_new(Shake::new(2., 0.1, packet_sender.translation));
+        output.burst_left = output.burst_left.saturating_sub(1);
+
+        commands.entity(server_entity).insert_if_new(Shake::new(
+            2.,
+            0.1,
+            packet_sender.translation,
+        ));
 
         let cables = get_adj_cables(packet_sender.translation.truncate(), &cables, &grid);
 
-        //spawns packets into all adjacent cables
+        //spawns packets into all adjacent cables on active ports
         for (cable_pos, adj_space) in cables {
+            let port = ADJ_SPACE.iter().position(|&a| a == adj_space).unwrap();
+            if output.unlocked.ports && !output.ports[port] {
+                continue;
+            }
+
+            // what was picked before an upgrade was undone waits for it to come back
+            let packet_type = match output.unlocked.heavy {
+                true => output.packet_type,
+                false => PacketType::Basic,
+            };
+            let mut sprite = Sprite::from_image(asset_server.load("player_packet.png"));
+            if packet_type == PacketType::Heavy {
+                sprite.color = Color::srgb(1., 0.6, 0.2);
+            }
+
             commands.spawn((
                 PlayerPacket,
-                Packet::new(adj_space, PacketType::Basic),
-                Sprite::from_image(asset_server.load("player_packet.png")),
+                Packet::new(adj_space, packet_type, &balance),
+                sprite,
                 Transform::from_translation(
                     (cable_pos - adj_space * SPRITE_SIZE / 2.05).extend(2.),
                 ),
@@ -64,3 +197,63 @@ fn create_packets(
         }
     }
 }
+
+//arrow keys toggle ports, T switches the packet type and B toggles burst mode for the hovered server
+fn configure_servers(
+    mut servers: Query<&mut ServerOutput>,
+    hovered_item: Res<HoveredItem>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(mut output) = hovered_item.0.and_then(|e| servers.get_mut(e).ok()) else {
+        return;
+    };
+
+    if output.unlocked.ports {
+        for (port, key) in PORT_KEYS.iter().enumerate() {
+            if keys.just_pressed(*key) {
+                output.ports[port] = !output.ports[port];
+            }
+        }
+    }
+
+    if output.unlocked.heavy && keys.just_pressed(KeyCode::KeyT) {
+        output.packet_type = match output.packet_type {
+            PacketType::Heavy => PacketType::Basic,
+            _ => PacketType::Heavy,
+        };
+    }
+
+    if output.unlocked.burst && keys.just_pressed(KeyCode::KeyB) {
+        output.burst = !output.burst;
+        output.burst_left = 0;
+    }
+}
+
+fn update_details(mut servers: Query<(&ServerOutput, &mut HoverDetails), Changed<ServerOutput>>) {
+    for (output, mut details) in &mut servers {
+        let mut lines = Vec::new();
+
+        if output.unlocked.ports {
+            let ports: Vec<&str> = ["Left", "Down", "Right", "Up"]
+                .into_iter()
+                .zip(output.ports)
+                .filter(|&(_, active)| active)
+                .map(|(name, _)| name)
+                .collect();
+            lines.push(format!("Ports (arrows): {}", ports.join(", ")));
+        }
+        if output.unlocked.heavy {
+            let packets = match output.packet_type {
+                PacketType::Heavy => "Heavy",
+                _ => "Basic",
+            };
+            lines.push(format!("Packets (T): {packets}"));
+        }
+        if output.unlocked.burst {
+            let burst = if output.burst { "On" } else { "Off" };
+            lines.push(format!("Burst (B): {burst}"));
+        }
+
+        details.0 = lines.join("\n");
+    }
+}
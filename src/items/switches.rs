@@ -1,55 +1,173 @@
 use bevy::prelude::*;
 
-use crate::{game::InGame, grid::Grid};
+use crate::{
+    balance::Balance,
+    camera::SPRITE_SIZE,
+    game::{EditorStates, GameStates, InGame},
+    grid::Grid,
+    shop::shop_items::{ItemType, ShopPosition},
+};
 
 use super::{
+    cables::{Cable, get_adj_cables},
+    items_ui::HoveredItem,
     packets::{EnemyPacket, Packet, PlayerPacket},
     projectiles::{Projectile, ProjectileType},
+    registry::{ItemInfo, RegisterItem},
 };
 
+// how far a switch can shoot (in cells) before any upgrades
+pub const BASE_RANGE: f32 = 4.;
+
 #[derive(Component)]
-#[require(InGame, ProjectileType)]
+pub struct TargetRange(pub f32);
+impl Default for TargetRange {
+    fn default() -> Self {
+        TargetRange(BASE_RANGE * SPRITE_SIZE)
+    }
+}
+
+#[derive(Component)]
+#[require(InGame, ProjectileType, TargetRange)]
 pub struct Switch;
 
+#[derive(Component)]
+#[require(InGame)]
+pub struct RangeIndicator;
+
 pub struct SwitchesPlugin;
 
 impl Plugin for SwitchesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, shoot_projectiles);
+        app.register_item(
+            ItemType::Switch,
+            ItemInfo {
+                name: "Switch",
+                sprite: "switch.png",
+                price: 25,
+                footprint: UVec2::ONE,
+                key: "Switch",
+                connectable: true,
+                shop_slot: Some(1),
+                spawn: |entity| {
+                    entity.insert(Switch);
+                },
+            },
+        );
+        app.add_systems(OnEnter(GameStates::InGame), init_range_indicator);
+        app.add_systems(
+            Update,
+            shoot_projectiles
+                .run_if(in_state(GameStates::InGame))
+                .run_if(in_state(EditorStates::Playing)),
+        );
+        app.add_systems(Update, show_range.run_if(in_state(GameStates::InGame)));
     }
 }
 
 fn shoot_projectiles(
-    player_packets: Query<(Entity, &Transform, &Packet), With<PlayerPacket>>,
-    enemy_packets: Query<(Entity, &Transform), With<EnemyPacket>>,
+    mut player_packets: Query<(Entity, &mut Transform, &mut Packet), With<PlayerPacket>>,
+    enemy_packets: Query<(Entity, &Transform), (With<EnemyPacket>, Without<PlayerPacket>)>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    switches: Query<(&GlobalTransform, &Switch, &ProjectileType)>,
+    switches: Query<(&GlobalTransform, &TargetRange, &ProjectileType), With<Switch>>,
+    cables: Query<&Cable>,
     grid: ResMut<Grid>,
+    balance: Res<Balance>,
 ) {
-    for (packet_entity, pos, packet) in &player_packets {
-        if let Some((t_switch, _, &projectile_type)) = grid
+    for (packet_entity, mut pos, mut packet) in &mut player_packets {
+        let Some((t_switch, range, &projectile_type)) = grid
             .get_element(pos.translation.truncate())
             .and_then(|e| switches.get(e).ok())
-        {
-            if let Some((target, _)) = enemy_packets.iter().max_by(|&(_, t1), &(_, t2)| {
-                t1.translation
-                    .distance(pos.translation)
-                    .total_cmp(&t2.translation.distance(pos.translation))
-                    .reverse()
-            }) {
-                commands.spawn((
-                    //spawns a projectile when your own packet reaches the switch
-                    Projectile {
-                        target,
-                        projectile_type,
-                        dmg_multi: packet.dmg_multi,
-                    },
-                    Sprite::from_image(asset_server.load("projectile.png")),
-                    Transform::from_translation(t_switch.translation()),
-                ));
-            }
+        else {
+            continue;
+        };
+
+        let switch_pos = t_switch.translation().truncate();
+
+        //only enemy packets inside the switch's range can be targeted
+        let target = enemy_packets
+            .iter()
+            .map(|(e, t)| (e, t.translation.truncate().distance(switch_pos)))
+            .filter(|&(_, dist)| dist <= range.0)
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+
+        if let Some((target, _)) = target {
+            commands.spawn((
+                //spawns a projectile when your own packet reaches the switch
+                Projectile::new(
+                    target,
+                    projectile_type,
+                    packet.dmg_multi * packet.stats(&balance).shot_multi,
+                ),
+                Sprite::from_image(asset_server.load("projectile.png")),
+                Transform::from_translation(t_switch.translation()),
+            ));
             commands.entity(packet_entity).despawn();
+            continue;
         }
+
+        //nothing to shoot at, so the packet goes through the switch (straight ahead if possible)
+        let mut cables: Vec<(Vec2, Vec2)> = get_adj_cables(switch_pos, &cables, &grid)
+            .into_iter()
+            .filter(|(_, adj_space)| *adj_space != -packet.dir)
+            .collect();
+        cables.sort_by_key(|(_, adj_space)| *adj_space != packet.dir);
+
+        let Some(&(cable_pos, adj_space)) = cables.first() else {
+            commands.entity(packet_entity).despawn();
+            continue;
+        };
+
+        packet.dir = adj_space;
+        pos.translation = (cable_pos - adj_space * SPRITE_SIZE / 2.05).extend(2.);
     }
 }
+
+fn init_range_indicator(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        RangeIndicator,
+        Name::new("Range indicator"),
+        Mesh2d(meshes.add(Circle::new(1.))),
+        MeshMaterial2d(materials.add(Color::Srgba(Srgba::new(1., 1., 1., 0.1)))),
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+}
+
+//draws the range circle of the hovered switch, or of the switch being dragged from the shop
+fn show_range(
+    placed_switches: Query<(&Transform, &TargetRange), (With<Switch>, Without<RangeIndicator>)>,
+    shop_items: Query<(&Transform, &ShopPosition, &ItemType), Without<RangeIndicator>>,
+    hovered_item: Res<HoveredItem>,
+    mut indicator: Query<(&mut Transform, &mut Visibility), With<RangeIndicator>>,
+) {
+    let Ok((mut t_indicator, mut visibility)) = indicator.get_single_mut() else {
+        return;
+    };
+
+    let hovered = hovered_item
+        .0
+        .and_then(|e| placed_switches.get(e).ok())
+        .map(|(t, range)| (t.translation.truncate(), range.0));
+
+    let dragged = shop_items
+        .iter()
+        .find(|(t, shop_pos, item_type)| {
+            matches!(item_type, ItemType::Switch) && t.translation.truncate() != shop_pos.0
+        })
+        .map(|(t, _, _)| (t.translation.truncate(), TargetRange::default().0));
+
+    let Some((center, range)) = dragged.or(hovered) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Visible;
+    t_indicator.translation = center.extend(5.);
+    t_indicator.scale = Vec3::splat(range);
+}
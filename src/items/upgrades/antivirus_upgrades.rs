@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::{
+    balance::UpgradeTable,
+    items::antivirus::{Antivirus, ScanRate},
+};
+
+use super::Upgradable;
+
+impl Upgradable for Antivirus {
+    type Data = &'static mut ScanRate;
+
+    fn init_price(&self) -> i32 {
+        20
+    }
+
+    fn upgrade(
+        &self,
+        level: u32,
+        data: &mut bevy::ecs::query::QueryItem<Self::Data>,
+        table: &UpgradeTable,
+    ) -> Option<i32> {
+        let scan_rate = data;
+
+        match level {
+            0 => {
+                scan_rate.0 =
+                    Timer::from_seconds(table.value(0, "scan_rate", 1.), TimerMode::Repeating); //scans the cables more often
+                Some(30) //upgrade cost
+            }
+            1 => {
+                scan_rate.0 =
+                    Timer::from_seconds(table.value(1, "scan_rate", 0.6), TimerMode::Repeating);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn reset(&self, data: &mut bevy::ecs::query::QueryItem<Self::Data>) {
+        **data = ScanRate::default();
+    }
+}
@@ -5,13 +5,25 @@ use bevy::{
     prelude::*,
 };
 
-use crate::{camera::SPRITE_SIZE, shop::currency::Currency};
+use crate::{
+    balance::{Balance, UpgradeTable},
+    camera::SPRITE_SIZE,
+    game::EditorStates,
+    grid::terrain::Locked,
+    history::{Action, History},
+    shop::{currency::Currency, shop_items::ItemType},
+};
 
-use super::{routers::Router, servers::Server, switches::Switch};
+use super::{
+    antivirus::Antivirus, registry::ItemRegistry, routers::Router, servers::Server,
+    switches::Switch, ups::Ups,
+};
 
+pub mod antivirus_upgrades;
 pub mod router_upgrades;
 pub mod server_upgrades;
 pub mod switch_upgrades;
+pub mod ups_upgrades;
 
 pub const UPGRADE_TIME: f32 = 1.0;
 
@@ -23,6 +35,10 @@ pub struct UpgradeTimer {
 #[derive(Component)]
 pub struct Upgrading;
 
+// an item put back on the grid or with an upgrade undone goes through its upgrades again, up to this level
+#[derive(Component)]
+pub struct RestoreLevel(pub u32);
+
 pub struct UpgradesPlugin;
 
 impl Plugin for UpgradesPlugin {
@@ -38,9 +54,23 @@ impl Plugin for UpgradesPlugin {
                 init_upgrades::<Server>,
                 init_upgrades::<Switch>,
                 init_upgrades::<Router>,
+                init_upgrades::<Antivirus>,
+                init_upgrades::<Ups>,
                 upgrade::<Server>,
                 upgrade::<Switch>,
                 upgrade::<Router>,
+                upgrade::<Antivirus>,
+                upgrade::<Ups>,
+                reapply_upgrades::<Server>,
+                reapply_upgrades::<Switch>,
+                reapply_upgrades::<Router>,
+                reapply_upgrades::<Antivirus>,
+                reapply_upgrades::<Ups>,
+                restore_upgrades::<Server>,
+                restore_upgrades::<Switch>,
+                restore_upgrades::<Router>,
+                restore_upgrades::<Antivirus>,
+                restore_upgrades::<Ups>,
             ),
         );
     }
@@ -53,7 +83,16 @@ pub trait Upgradable {
     fn init_price(&self) -> i32;
 
     // upgrades the item to this level, returns the cost for the next upgrade, if there is one
-    fn upgrade(&self, level: u32, data: &mut QueryItem<Self::Data>) -> Option<i32>;
+    // values named in the item's balance table take precedence over the compiled ones
+    fn upgrade(
+        &self,
+        level: u32,
+        data: &mut QueryItem<Self::Data>,
+        table: &UpgradeTable,
+    ) -> Option<i32>;
+
+    // takes back what the upgrades did, as the item was before its first one
+    fn reset(&self, data: &mut QueryItem<Self::Data>);
 }
 
 #[derive(Component)]
@@ -72,13 +111,16 @@ impl From<i32> for UpgradeLevel {
 }
 
 fn init_upgrades<T: Upgradable + Component>(
-    items: Query<(Entity, &T), Added<T>>,
+    items: Query<(Entity, &T, &ItemType), (Added<T>, Without<Locked>)>,
+    balance: Res<Balance>,
+    registry: Res<ItemRegistry>,
     mut commands: Commands,
 ) {
-    for (id, item) in &items {
+    for (id, item, item_type) in &items {
+        let table = balance.upgrades(registry.get(*item_type).key);
         commands
             .entity(id)
-            .insert(UpgradeLevel::from(item.init_price()))
+            .insert(UpgradeLevel::from(table.init_price(item.init_price())))
             .observe(start_upgrade::<T>)
             .observe(end_upgrade::<T>);
     }
@@ -89,6 +131,7 @@ fn start_upgrade<T: Upgradable + Component>(
     trigger: Trigger<Pointer<Down>>,
     mut items: Query<(&Transform, &mut UpgradeLevel)>,
     currency: ResMut<Currency>,
+    editor: Res<State<EditorStates>>,
     mut upgrade_timer: ResMut<UpgradeTimer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
@@ -100,7 +143,7 @@ fn start_upgrade<T: Upgradable + Component>(
     let Some(price) = level.next_price else {
         return;
     };
-    if currency.value < price {
+    if currency.value < editor.price(price as u32) as i32 {
         return;
     }
 
@@ -133,23 +176,29 @@ fn end_upgrade<T: Upgradable + Component>(
 }
 
 fn upgrade<T: Upgradable + Component>(
-    mut items: Query<(&T, &mut UpgradeLevel, T::Data)>,
+    mut items: Query<(&T, &ItemType, &mut UpgradeLevel, T::Data)>,
+    balance: Res<Balance>,
+    registry: Res<ItemRegistry>,
     time: Res<Time>,
     mut currency: ResMut<Currency>,
+    editor: Res<State<EditorStates>>,
     mut upgrade_timer: ResMut<UpgradeTimer>,
+    mut history: ResMut<History>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut rect: Query<&mut Mesh2d, With<Upgrading>>,
 ) {
     let Some(entity) = upgrade_timer.entity else {
         return;
     };
-    let Ok((item, mut level, mut data)) = items.get_mut(entity) else {
+    let Ok((item, item_type, mut level, mut data)) = items.get_mut(entity) else {
         return;
     };
 
     let Some(price) = level.next_price else {
         return;
     };
+    //upgrades are free in the editor, like placing
+    let price = editor.price(price as u32) as i32;
     if currency.value < price {
         return;
     }
@@ -165,10 +214,74 @@ fn upgrade<T: Upgradable + Component>(
 
     if upgrade_timer.timer.just_finished() {
         currency.value -= price;
+        history.record(Action::Upgrade {
+            entity,
+            from: level.level,
+            price,
+        });
 
-        level.next_price = item.upgrade(level.level, &mut data);
+        let table = balance.upgrades(registry.get(*item_type).key);
+        level.next_price =
+            table.next_price(level.level, item.upgrade(level.level, &mut data, table));
         level.level += 1;
 
         upgrade_timer.timer = Timer::new(Duration::from_secs_f32(UPGRADE_TIME), TimerMode::Once);
     }
 }
+
+//items already on the grid go through their upgrades again with the new balance
+fn reapply_upgrades<T: Upgradable + Component>(
+    mut items: Query<(&T, &ItemType, &mut UpgradeLevel, T::Data)>,
+    balance: Res<Balance>,
+    registry: Res<ItemRegistry>,
+) {
+    if !balance.is_changed() {
+        return;
+    }
+
+    for (item, item_type, mut level, mut data) in &mut items {
+        let table = balance.upgrades(registry.get(*item_type).key);
+        let to = level.level;
+        replay(item, &mut level, to, &mut data, table);
+    }
+}
+
+fn restore_upgrades<T: Upgradable + Component>(
+    mut items: Query<(
+        Entity,
+        &T,
+        &ItemType,
+        &mut UpgradeLevel,
+        &RestoreLevel,
+        T::Data,
+    )>,
+    balance: Res<Balance>,
+    registry: Res<ItemRegistry>,
+    mut commands: Commands,
+) {
+    for (entity, item, item_type, mut level, restore, mut data) in &mut items {
+        let table = balance.upgrades(registry.get(*item_type).key);
+        // an undone upgrade goes back down from the start
+        if restore.0 < level.level {
+            item.reset(&mut data);
+        }
+        replay(item, &mut level, restore.0, &mut data, table);
+        commands.entity(entity).remove::<RestoreLevel>();
+    }
+}
+
+//upgrades an item level by level from the start, up to `to`
+fn replay<T: Upgradable>(
+    item: &T,
+    level: &mut UpgradeLevel,
+    to: u32,
+    data: &mut QueryItem<T::Data>,
+    table: &UpgradeTable,
+) {
+    let mut next_price = Some(table.init_price(item.init_price()));
+    for l in 0..to {
+        next_price = table.next_price(l, item.upgrade(l, data, table));
+    }
+    level.level = to;
+    level.next_price = next_price;
+}
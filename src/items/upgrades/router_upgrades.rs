@@ -1,4 +1,7 @@
-use crate::items::routers::{DamageMultiplier, Router};
+use crate::{
+    balance::UpgradeTable,
+    items::routers::{DamageMultiplier, Router},
+};
 
 use super::Upgradable;
 
@@ -13,23 +16,28 @@ impl Upgradable for Router {
         &self,
         level: u32,
         data: &mut bevy::ecs::query::QueryItem<Self::Data>,
+        table: &UpgradeTable,
     ) -> Option<i32> {
         let damage_multiply = data; //multiplies damage of packets
 
         match level {
             0 => {
-                damage_multiply.0 = 1;
+                damage_multiply.0 = table.value(0, "damage_multiplier", 1.) as i32;
                 Some(20) //cost of upgrading
             }
             1 => {
-                damage_multiply.0 = 2;
+                damage_multiply.0 = table.value(1, "damage_multiplier", 2.) as i32;
                 Some(30)
             }
             2 => {
-                damage_multiply.0 = 3;
+                damage_multiply.0 = table.value(2, "damage_multiplier", 3.) as i32;
                 None
             }
             _ => None,
         }
     }
+
+    fn reset(&self, data: &mut bevy::ecs::query::QueryItem<Self::Data>) {
+        **data = DamageMultiplier::default();
+    }
 }
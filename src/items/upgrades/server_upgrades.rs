@@ -1,11 +1,14 @@
 use bevy::prelude::*;
 
-use crate::items::servers::{FireRate, Server};
+use crate::{
+    balance::UpgradeTable,
+    items::servers::{FireRate, Server, ServerOutput, ServerUnlocks},
+};
 
 use super::Upgradable;
 
 impl Upgradable for Server {
-    type Data = &'static mut FireRate;
+    type Data = (&'static mut FireRate, &'static mut ServerOutput);
 
     fn init_price(&self) -> i32 {
         10
@@ -15,19 +18,34 @@ impl Upgradable for Server {
         &self,
         level: u32,
         data: &mut bevy::ecs::query::QueryItem<Self::Data>,
+        table: &UpgradeTable,
     ) -> Option<i32> {
-        let fire_rate = data;
+        let (fire_rate, output) = data;
 
         match level {
             0 => {
-                fire_rate.0 = Timer::from_seconds(2., TimerMode::Repeating); //upgrading makes the server send packets faster
+                fire_rate.0 =
+                    Timer::from_seconds(table.value(0, "fire_rate", 2.), TimerMode::Repeating); //upgrading makes the server send packets faster
+                output.unlocked.ports = true; //and lets you pick which ports it sends to
                 Some(20) //upgrade cost
             }
             1 => {
-                fire_rate.0 = Timer::from_seconds(1.5, TimerMode::Repeating);
+                fire_rate.0 =
+                    Timer::from_seconds(table.value(1, "fire_rate", 1.5), TimerMode::Repeating);
+                output.unlocked.heavy = true;
+                Some(30)
+            }
+            2 => {
+                output.unlocked.burst = true;
                 None
             }
             _ => None,
         }
     }
+
+    fn reset(&self, data: &mut bevy::ecs::query::QueryItem<Self::Data>) {
+        let (fire_rate, output) = data;
+        **fire_rate = FireRate::default();
+        output.unlocked = ServerUnlocks::default(); //the ports, type and burst picked stay for when they're unlocked again
+    }
 }
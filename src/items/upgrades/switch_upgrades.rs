@@ -1,11 +1,18 @@
 use bevy::prelude::*;
 
-use crate::items::{projectiles::ProjectileType, switches::Switch};
+use crate::{
+    balance::UpgradeTable,
+    camera::SPRITE_SIZE,
+    items::{
+        projectiles::ProjectileType,
+        switches::{BASE_RANGE, Switch, TargetRange},
+    },
+};
 
 use super::Upgradable;
 
 impl Upgradable for Switch {
-    type Data = &'static mut ProjectileType;
+    type Data = (&'static mut ProjectileType, &'static mut TargetRange);
 
     fn init_price(&self) -> i32 {
         15
@@ -15,19 +22,40 @@ impl Upgradable for Switch {
         &self,
         level: u32,
         data: &mut bevy::ecs::query::QueryItem<Self::Data>,
+        table: &UpgradeTable,
     ) -> Option<i32> {
-        let projectile_type = data;
+        let (projectile_type, range) = data;
 
         match level {
             0 => {
                 projectile_type.set_if_neq(ProjectileType::Mid); //improves the projectile type
+                range.0 = table.value(0, "range", BASE_RANGE + 1.) * SPRITE_SIZE; //and how far the switch can shoot
                 Some(20) //upgrade cost
             }
             1 => {
                 projectile_type.set_if_neq(ProjectileType::Advanced);
+                range.0 = table.value(1, "range", BASE_RANGE + 2.) * SPRITE_SIZE;
+                Some(30)
+            }
+            2 => {
+                projectile_type.set_if_neq(ProjectileType::Splash); //projectiles now hit everything around the target
+                Some(40)
+            }
+            3 => {
+                projectile_type.set_if_neq(ProjectileType::Chain); //projectiles jump between targets
+                Some(50)
+            }
+            4 => {
+                projectile_type.set_if_neq(ProjectileType::Piercing); //projectiles go through every packet in their way
                 None
             }
             _ => None,
         }
     }
+
+    fn reset(&self, data: &mut bevy::ecs::query::QueryItem<Self::Data>) {
+        let (projectile_type, range) = data;
+        projectile_type.set_if_neq(ProjectileType::default());
+        **range = TargetRange::default();
+    }
 }
@@ -0,0 +1,43 @@
+use crate::{
+    balance::UpgradeTable,
+    items::ups::{Regen, Ups},
+};
+
+use super::Upgradable;
+
+impl Upgradable for Ups {
+    type Data = &'static mut Regen;
+
+    fn init_price(&self) -> i32 {
+        25
+    }
+
+    fn upgrade(
+        &self,
+        level: u32,
+        data: &mut bevy::ecs::query::QueryItem<Self::Data>,
+        table: &UpgradeTable,
+    ) -> Option<i32> {
+        let regen = data;
+
+        match level {
+            0 => {
+                regen.rate = table.value(0, "rate", 2.); //restores health faster
+                regen.cap = table.value(0, "cap", 0.8); //and up to a higher value
+                Some(35) //upgrade cost
+            }
+            1 => {
+                regen.rate = table.value(1, "rate", 3.);
+                regen.cap = table.value(1, "cap", 1.);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn reset(&self, data: &mut bevy::ecs::query::QueryItem<Self::Data>) {
+        let base = Regen::default();
+        data.rate = base.rate;
+        data.cap = base.cap;
+    }
+}
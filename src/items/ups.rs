@@ -0,0 +1,114 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    game::{EditorStates, GameStates, InGame},
+    grid::Grid,
+    health::{PcHealth, UpdateHealthEvent},
+    shop::shop_items::ItemType,
+};
+
+use super::{
+    cables::{Cable, cable_end, get_adj_cables},
+    pcs::{Destroyed, PC},
+    registry::{ItemInfo, RegisterItem},
+};
+
+// seconds a PC has to go without damage before a UPS starts restoring its health
+pub const IDLE_TIME: f32 = 5.;
+
+#[derive(Component)]
+pub struct Regen {
+    pub rate: f32,   // health per second
+    pub cap: f32,    // the UPS doesn't restore health past this fraction of the PC's max health
+    pub stored: f32, // health restored that isn't a whole point yet
+}
+impl Default for Regen {
+    fn default() -> Self {
+        Self {
+            rate: 1.,
+            cap: 0.6,
+            stored: 0.,
+        }
+    }
+}
+
+#[derive(Component)]
+#[require(InGame, Regen)]
+pub struct Ups;
+
+pub struct UpsPlugin;
+
+impl Plugin for UpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_item(
+            ItemType::Ups,
+            ItemInfo {
+                name: "UPS",
+                sprite: "ups.png",
+                price: 45,
+                footprint: UVec2::ONE,
+                key: "Ups",
+                connectable: true,
+                shop_slot: Some(6),
+                spawn: |entity| {
+                    entity.insert(Ups);
+                },
+            },
+        );
+        app.add_systems(
+            Update,
+            regenerate
+                .run_if(in_state(GameStates::InGame))
+                .run_if(in_state(EditorStates::Playing)),
+        );
+    }
+}
+
+//restores the health of the PC the UPS is cabled to, once no enemy packet reached that PC for a while
+fn regenerate(
+    mut power_supplies: Query<(&Transform, &mut Regen), With<Ups>>,
+    mut pcs: Query<&mut PcHealth, (With<PC>, Without<Destroyed>)>,
+    cables: Query<&Cable>,
+    grid: Res<Grid>,
+    time: Res<Time>,
+    mut update_health_writer: EventWriter<UpdateHealthEvent>,
+) {
+    for mut pc_health in &mut pcs {
+        pc_health.regenerating = false;
+    }
+
+    // health already restored this frame, for PCs powered by more than one UPS
+    let mut restored: HashMap<Entity, i32> = HashMap::new();
+
+    for (pos, mut regen) in &mut power_supplies {
+        let powered_pc = get_adj_cables(pos.translation.truncate(), &cables, &grid)
+            .into_iter()
+            .filter_map(|(cable_pos, adj_space)| {
+                grid.get_element(cable_end(cable_pos, adj_space, &cables, &grid))
+            })
+            .find(|&e| pcs.contains(e));
+
+        let Some(pc) = powered_pc else {
+            regen.stored = 0.;
+            continue;
+        };
+        let mut pc_health = pcs.get_mut(pc).unwrap();
+        let already_restored = restored.entry(pc).or_default();
+        let cap = (pc_health.max as f32 * regen.cap) as i32;
+
+        if pc_health.since_damage < IDLE_TIME || pc_health.value + *already_restored >= cap {
+            regen.stored = 0.;
+            continue;
+        }
+
+        pc_health.regenerating = true;
+        regen.stored += regen.rate * time.delta_secs();
+
+        let amount = (regen.stored as i32).min(cap - pc_health.value - *already_restored);
+        if amount > 0 {
+            regen.stored -= amount as f32;
+            *already_restored += amount;
+            update_health_writer.send(UpdateHealthEvent(pc, amount));
+        }
+    }
+}
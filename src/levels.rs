@@ -25,22 +25,114 @@ impl WaveManager {
             j: 0,
         }
     }
+    pub fn new(level: Level) -> WaveManager {
+        WaveManager {
+            timer: level.get_timer(0, 0),
+            level: Some(level),
+            i: 0,
+            j: 0,
+        }
+    }
     pub fn get_index(&self) -> (usize, usize) {
         (self.i, self.j)
     }
+    //the packets of the current wave aren't all sent yet
+    pub fn sending(&self) -> bool {
+        let Some(level) = self.level.as_ref() else {
+            return false;
+        };
+        level
+            .waves
+            .get(self.i)
+            .is_some_and(|wave| self.j + 1 < wave.wave.len())
+    }
+    pub fn save(&self) -> Option<SavedWaves> {
+        let level = self.level.as_ref()?;
+        Some(SavedWaves {
+            waves: level.waves.clone(),
+            loss_rule: level.loss_rule,
+            destroyed_pc: level.destroyed_pc,
+            i: self.i,
+            j: self.j,
+            duration: self.timer.duration().as_secs_f32(),
+            elapsed: self.timer.elapsed_secs(),
+        })
+    }
+    //picks the waves and rules up where they were saved
+    pub fn resume(saved: SavedWaves, mut level: Level) -> WaveManager {
+        level.waves = saved.waves;
+        level.set_rules(saved.loss_rule, saved.destroyed_pc);
+        let mut timer = Timer::from_seconds(saved.duration, TimerMode::Once);
+        timer.set_elapsed(Duration::from_secs_f32(saved.elapsed));
+        WaveManager {
+            timer,
+            level: Some(level),
+            i: saved.i,
+            j: saved.j,
+        }
+    }
     pub fn get_sandbox_timer() -> Timer {
         return Timer::new(Duration::from_secs_f32(3.), TimerMode::Once);
     }
 }
-#[derive(Clone)]
+// where the waves of a level were when the game was saved
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedWaves {
+    waves: Vec<Wave>,
+    loss_rule: LossRule,
+    destroyed_pc: DestroyedPcRule,
+    i: usize,
+    j: usize,
+    duration: f32,
+    elapsed: f32,
+}
+
+// packets sent one after the other, each with the seconds to wait before the next one
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
 pub struct Wave {
     wave: Vec<(PacketType, f32)>,
 }
+impl Wave {
+    pub fn new(wave: Vec<(PacketType, f32)>) -> Self {
+        Self { wave }
+    }
+    pub fn packets(&self) -> &[(PacketType, f32)] {
+        &self.wave
+    }
+}
 #[derive(Clone)]
 pub struct Level {
     waves: Vec<Wave>,
+    pub loss_rule: LossRule,
+    pub destroyed_pc: DestroyedPcRule,
+}
+
+// which PCs need to fall for the level to be lost
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum LossRule {
+    #[default]
+    AnyPc,
+    AllPcs,
+}
+
+// what happens to enemy packets reaching an already destroyed PC
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum DestroyedPcRule {
+    #[default]
+    Discard,
+    Continue,
 }
 impl Level {
+    //waves a grid file brings along, they replace the built-in ones
+    pub fn set_waves(&mut self, waves: Vec<Wave>) {
+        self.waves = waves;
+    }
+    //the loss rules come from the grid file of the level
+    pub fn set_rules(&mut self, loss_rule: LossRule, destroyed_pc: DestroyedPcRule) {
+        self.loss_rule = loss_rule;
+        self.destroyed_pc = destroyed_pc;
+    }
     pub fn valid(&self, i: usize, j: usize) -> bool {
         return i < self.waves.len() && j < self.waves[i].wave.len();
     }
@@ -127,10 +219,12 @@ pub fn get_level(level: GameLevels) -> Level {
         Wave { wave: packets }
     }
 
-    //create the waves for each level
+    //create the waves for each level, the loss rules come from its grid file
     return match level {
         GameLevels::Sandbox => Level {
             waves: vec![create_wave(vec![(PacketType::Basic, 1.0)])],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
         },
         GameLevels::Easy => Level {
             waves: vec![
@@ -149,6 +243,8 @@ pub fn get_level(level: GameLevels) -> Level {
                     (PacketType::Basic, 3.0),
                 ]),
             ],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
         },
         GameLevels::Medium => Level {
             waves: vec![
@@ -174,6 +270,8 @@ pub fn get_level(level: GameLevels) -> Level {
                     (PacketType::Basic, 0.6),
                 ]),
             ],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
         },
         GameLevels::Hard => Level {
             waves: vec![
@@ -206,6 +304,8 @@ pub fn get_level(level: GameLevels) -> Level {
                     (PacketType::Basic, 0.5),
                 ]),
             ],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
         },
         GameLevels::Expert => Level {
             waves: vec![
@@ -239,6 +339,18 @@ pub fn get_level(level: GameLevels) -> Level {
                     (PacketType::Advanced, 0.5),
                 ]),
             ],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
+        },
+        //custom levels usually bring their own waves
+        GameLevels::Custom => Level {
+            waves: vec![create_wave(vec![
+                (PacketType::Basic, 1.0),
+                (PacketType::Basic, 1.0),
+                (PacketType::Basic, 1.0),
+            ])],
+            loss_rule: LossRule::default(),
+            destroyed_pc: DestroyedPcRule::default(),
         },
     };
 }
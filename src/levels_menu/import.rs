@@ -0,0 +1,307 @@
+use std::fs;
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui::FocusPolicy,
+};
+
+use crate::{
+    clipboard,
+    game::{GameLevels, GameStates},
+    grid::{level_code::import_code, save_load::CustomLevel},
+    items::registry::ItemRegistry,
+};
+
+use super::{
+    LevelsMenu,
+    ui::{ButtonType, ImportBtnPress, ImportCancelBtnPress, ImportPlayBtnPress},
+};
+
+const DIALOG_BACKGROUND: Color = Color::srgb(0.1, 0.1, 0.1);
+const DIALOG_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+// end of the code that's shown, codes are longer than the dialog
+const SHOWN_CODE_LEN: usize = 40;
+
+// the code typed or dropped into the dialog, and why the last try didn't work
+#[derive(Resource, Default)]
+pub struct ImportCode {
+    pub code: String,
+    pub status: String,
+}
+
+#[derive(Component)]
+pub struct ImportDialog;
+
+#[derive(Component)]
+pub enum ImportText {
+    Code,
+    Status,
+}
+
+pub struct ImportPlugin;
+
+impl Plugin for ImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImportCode>();
+        app.add_systems(
+            Update,
+            (
+                open_dialog,
+                close_dialog,
+                type_code,
+                drop_code,
+                play_code,
+                update_dialog,
+            )
+                .chain()
+                .run_if(in_state(GameStates::LevelsMenu)),
+        );
+    }
+}
+
+fn open_dialog(
+    mut event: EventReader<ImportBtnPress>,
+    dialog: Query<(), With<ImportDialog>>,
+    mut import: ResMut<ImportCode>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    if event.read().len() == 0 || !dialog.is_empty() {
+        return;
+    }
+    *import = ImportCode {
+        status: "paste a level code with Ctrl+V, type one or drop a file with one".to_string(),
+        ..default()
+    };
+    spawn_dialog(&mut commands, &asset_server);
+}
+
+fn close_dialog(
+    mut event: EventReader<ImportCancelBtnPress>,
+    dialog: Query<Entity, With<ImportDialog>>,
+    mut commands: Commands,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+    for dialog in &dialog {
+        commands.entity(dialog).despawn_recursive();
+    }
+}
+
+//Enter plays the typed code, Ctrl+V pastes one
+fn type_code(
+    mut events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    dialog: Query<(), With<ImportDialog>>,
+    mut import: ResMut<ImportCode>,
+    mut play: EventWriter<ImportPlayBtnPress>,
+) {
+    if dialog.is_empty() {
+        events.clear();
+        return;
+    }
+    // shortcuts aren't text
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let modifier = ctrl
+        || keys.any_pressed([
+            KeyCode::AltLeft,
+            KeyCode::AltRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+    if ctrl && keys.just_pressed(KeyCode::KeyV) {
+        match clipboard::paste() {
+            Ok(text) => {
+                import.code = text.split_whitespace().collect();
+                import.status = "pasted a code".to_string();
+            }
+            Err(e) => import.status = format!("couldn't paste: {e}"),
+        }
+    }
+    if modifier {
+        events.clear();
+        return;
+    }
+
+    for ev in events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Backspace => {
+                import.code.pop();
+            }
+            Key::Enter => {
+                play.send(ImportPlayBtnPress);
+            }
+            Key::Character(text) => {
+                import
+                    .code
+                    .extend(text.chars().filter(|c| !c.is_whitespace()));
+            }
+            _ => {}
+        }
+    }
+}
+
+//a text file dropped on the window holds the code
+fn drop_code(
+    mut events: EventReader<FileDragAndDrop>,
+    dialog: Query<(), With<ImportDialog>>,
+    mut import: ResMut<ImportCode>,
+) {
+    for ev in events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = ev else {
+            continue;
+        };
+        if dialog.is_empty() {
+            continue;
+        }
+        match fs::read_to_string(path_buf) {
+            Ok(text) => {
+                import.code = text.split_whitespace().collect();
+                import.status = format!("read {}", path_buf.display());
+            }
+            Err(e) => import.status = format!("couldn't read {}: {e}", path_buf.display()),
+        }
+    }
+}
+
+//a valid code is played as a custom level
+fn play_code(
+    mut event: EventReader<ImportPlayBtnPress>,
+    mut import: ResMut<ImportCode>,
+    registry: Res<ItemRegistry>,
+    mut custom: ResMut<CustomLevel>,
+    mut next_state: ResMut<NextState<GameStates>>,
+    mut next_level: ResMut<NextState<GameLevels>>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+
+    let state = import_code(&import.code)
+        .map_err(|e| e.to_string())
+        .and_then(|state| state.validate(&registry).map(|_| state));
+    match state {
+        Ok(state) => {
+            info!("playing an imported level: {}", state.meta.title);
+            custom.0 = Some(state);
+            next_level.set(GameLevels::Custom);
+            next_state.set(GameStates::InGame);
+        }
+        Err(e) => import.status = format!("can't play this code: {e}"),
+    }
+}
+
+fn update_dialog(import: Res<ImportCode>, mut texts: Query<(&mut Text, &ImportText)>) {
+    if !import.is_changed() {
+        return;
+    }
+
+    for (mut text, import_text) in &mut texts {
+        text.0 = match import_text {
+            ImportText::Code => {
+                let skip = import.code.chars().count().saturating_sub(SHOWN_CODE_LEN);
+                let shown: String = import.code.chars().skip(skip).collect();
+                match skip {
+                    0 => format!("{shown}_"),
+                    _ => format!("...{shown}_"),
+                }
+            }
+            ImportText::Status => import.status.clone(),
+        };
+    }
+}
+
+//a box over the levels, with the code and what to do with it
+fn spawn_dialog(commands: &mut Commands, asset_server: &AssetServer) {
+    let font = TextFont {
+        font: asset_server.load("fonts/courbd.ttf"),
+        font_size: 18.0,
+        ..default()
+    };
+    let button = Node {
+        width: Val::Px(150.0),
+        height: Val::Px(45.0),
+        border: UiRect::all(Val::Px(5.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Name::new("import dialog"),
+            LevelsMenu,
+            ImportDialog,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            GlobalZIndex(1),
+            // the level buttons under it can't be clicked
+            FocusPolicy::Block,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(560.0),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(15.0),
+                        ..default()
+                    },
+                    BackgroundColor(DIALOG_BACKGROUND),
+                ))
+                .with_children(|dialog| {
+                    dialog.spawn((
+                        Text::new("IMPORT A LEVEL"),
+                        font.clone(),
+                        TextColor(Color::srgb(0.5, 0.0, 0.0)),
+                    ));
+                    dialog.spawn((ImportText::Code, Text::new("_"), font.clone()));
+                    dialog.spawn((
+                        ImportText::Status,
+                        Text::new(""),
+                        font.clone(),
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                    dialog
+                        .spawn(Node {
+                            column_gap: Val::Px(20.0),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            for (btn_type, label) in [
+                                (ButtonType::ImportPlay, "PLAY"),
+                                (ButtonType::ImportCancel, "CANCEL"),
+                            ] {
+                                row.spawn((
+                                    Button,
+                                    btn_type,
+                                    button.clone(),
+                                    BorderColor(Color::BLACK),
+                                    BackgroundColor(DIALOG_BUTTON),
+                                ))
+                                .with_child((
+                                    Text::new(label),
+                                    font.clone(),
+                                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                ));
+                            }
+                        });
+                });
+        });
+}
@@ -2,9 +2,13 @@ use bevy::prelude::*;
 use ui::{BackBtnPress, EasyBtnPress, ExpertBtnPress, HardBtnPress, MediumBtnPress};
 
 use crate::game::{GameLevels, GameStates, HighestLevel};
+use crate::levels_menu::import::ImportPlugin;
 use crate::levels_menu::ui::LevUIPlugin;
+use crate::levels_menu::user_levels::UserLevelsPlugin;
 
+pub mod import;
 pub mod ui;
+pub mod user_levels;
 
 #[derive(Component)]
 pub struct LevelsMenu;
@@ -13,7 +17,7 @@ pub struct LevelsPlugin;
 
 impl Plugin for LevelsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(LevUIPlugin);
+        app.add_plugins((LevUIPlugin, ImportPlugin, UserLevelsPlugin));
         app.add_systems(
             Update,
             (on_easy, on_medium, on_hard, on_expert, on_back)
@@ -23,10 +27,11 @@ impl Plugin for LevelsPlugin {
     }
 }
 
-//despawn the menu
+//despawn the menu, with the import dialog if it's open
 fn despawn_levels(mut commands: Commands, levels_menu: Query<Entity, With<LevelsMenu>>) {
-    let levels_menu = levels_menu.single();
-    commands.entity(levels_menu).despawn_recursive();
+    for levels_menu in &levels_menu {
+        commands.entity(levels_menu).despawn_recursive();
+    }
 }
 
 //get to a level or back to menu
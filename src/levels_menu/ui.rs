@@ -28,12 +28,29 @@ pub struct ExpertBtnPress;
 #[derive(Event)]
 pub struct BackBtnPress;
 
+#[derive(Event)]
+pub struct ImportBtnPress;
+
+#[derive(Event)]
+pub struct UserLevelBtnPress(pub usize);
+
+#[derive(Event)]
+pub struct ImportPlayBtnPress;
+
+#[derive(Event)]
+pub struct ImportCancelBtnPress;
+
 #[derive(Component)]
 pub enum ButtonType {
     Easy,
     Medium,
     Hard,
     Expert,
+    Import,
+    ImportPlay,
+    ImportCancel,
+    // index into UserLevels
+    UserLevel(usize),
     Back,
 }
 
@@ -46,6 +63,10 @@ impl Plugin for LevUIPlugin {
         app.add_event::<HardBtnPress>();
         app.add_event::<ExpertBtnPress>();
         app.add_event::<BackBtnPress>();
+        app.add_event::<ImportBtnPress>();
+        app.add_event::<ImportPlayBtnPress>();
+        app.add_event::<ImportCancelBtnPress>();
+        app.add_event::<UserLevelBtnPress>();
         app.add_systems(OnEnter(GameStates::LevelsMenu), setup);
         app.add_systems(
             Update,
@@ -71,6 +92,10 @@ fn button_system(
     mut hard: EventWriter<HardBtnPress>,
     mut expert: EventWriter<ExpertBtnPress>,
     mut back: EventWriter<BackBtnPress>,
+    mut import: EventWriter<ImportBtnPress>,
+    mut import_play: EventWriter<ImportPlayBtnPress>,
+    mut import_cancel: EventWriter<ImportCancelBtnPress>,
+    mut user_level: EventWriter<UserLevelBtnPress>,
 ) {
     for (interaction, btn_type, mut color, mut border_color, _children) in &mut interaction_query {
         match *interaction {
@@ -91,6 +116,18 @@ fn button_system(
                     ButtonType::Expert => {
                         expert.send(ExpertBtnPress);
                     }
+                    ButtonType::Import => {
+                        import.send(ImportBtnPress);
+                    }
+                    ButtonType::ImportPlay => {
+                        import_play.send(ImportPlayBtnPress);
+                    }
+                    ButtonType::ImportCancel => {
+                        import_cancel.send(ImportCancelBtnPress);
+                    }
+                    ButtonType::UserLevel(i) => {
+                        user_level.send(UserLevelBtnPress(*i));
+                    }
                     ButtonType::Back => {
                         back.send(BackBtnPress);
                     }
@@ -237,6 +274,32 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     },
                     TextColor(Color::srgb(0.9, 0.9, 0.9)),
                 ));
+            parent
+                .spawn((
+                    Button,
+                    ButtonType::Import,
+                    Node {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(5.0)),
+                        // horizontally center child text
+                        justify_content: JustifyContent::Center,
+                        // vertically center child text
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::BLACK),
+                    BackgroundColor(BACK_BUTTON),
+                ))
+                .with_child((
+                    Text::new("IMPORT"),
+                    TextFont {
+                        font: asset_server.load("fonts/courbd.ttf"),
+                        font_size: 33.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ));
             parent
                 .spawn((
                     Button,
@@ -0,0 +1,238 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use crate::{
+    encoding::Format,
+    game::{GameLevels, GameStates},
+    grid::{
+        format::GridMeta,
+        save_load::{CustomLevel, GridState},
+    },
+    items::registry::ItemRegistry,
+    user_data::user_levels_dir,
+};
+
+use super::{
+    LevelsMenu,
+    ui::{ButtonType, UserLevelBtnPress},
+};
+
+const LEVEL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const BROKEN_LEVEL: Color = Color::srgb(0.3, 0.1, 0.1);
+// pixels scrolled for each line of the mouse wheel
+const SCROLL_LINE: f32 = 30.;
+
+// a grid file of the user levels folder, or why it can't be played
+pub struct UserLevel {
+    pub path: PathBuf,
+    pub state: Result<GridState, String>,
+}
+
+impl UserLevel {
+    //the title of the level, the file name for levels without one
+    pub fn title(&self) -> String {
+        match &self.state {
+            Ok(state) if !state.meta.title.is_empty() => state.meta.title.clone(),
+            _ => self
+                .path
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+// the user levels as they were when the levels menu opened
+#[derive(Resource, Default)]
+pub struct UserLevels(pub Vec<UserLevel>);
+
+#[derive(Component)]
+struct UserLevelList;
+
+pub struct UserLevelsPlugin;
+
+impl Plugin for UserLevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UserLevels>();
+        app.add_systems(
+            OnEnter(GameStates::LevelsMenu),
+            (find_user_levels, spawn_user_levels).chain(),
+        );
+        app.add_systems(
+            Update,
+            (on_user_level, scroll_user_levels).run_if(in_state(GameStates::LevelsMenu)),
+        );
+    }
+}
+
+//reads every *.grid.json of the user levels folder, sorted by file name
+fn find_user_levels(mut levels: ResMut<UserLevels>, registry: Res<ItemRegistry>) {
+    levels.0.clear();
+    let Ok(entries) = fs::read_dir(user_levels_dir()) else {
+        return;
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".grid.json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let state = fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| GridState::parse(&bytes, Format::Json).map_err(|e| e.to_string()))
+            .and_then(|state| state.validate(&registry).map(|_| state));
+        levels.0.push(UserLevel { path, state });
+    }
+}
+
+//plays the level through the same grid loading as the campaign
+fn on_user_level(
+    mut event: EventReader<UserLevelBtnPress>,
+    levels: Res<UserLevels>,
+    mut custom: ResMut<CustomLevel>,
+    mut next_state: ResMut<NextState<GameStates>>,
+    mut next_level: ResMut<NextState<GameLevels>>,
+) {
+    for ev in event.read() {
+        let Some(Ok(state)) = levels.0.get(ev.0).map(|l| &l.state) else {
+            continue;
+        };
+        custom.0 = Some(state.clone());
+        next_level.set(GameLevels::Custom);
+        next_state.set(GameStates::InGame);
+    }
+}
+
+fn scroll_user_levels(
+    mut wheel: EventReader<MouseWheel>,
+    mut lists: Query<&mut ScrollPosition, With<UserLevelList>>,
+) {
+    for ev in wheel.read() {
+        let dy = match ev.unit {
+            MouseScrollUnit::Line => ev.y * SCROLL_LINE,
+            MouseScrollUnit::Pixel => ev.y,
+        };
+        for mut scroll in &mut lists {
+            scroll.offset_y -= dy;
+        }
+    }
+}
+
+//what's shown under a level's title
+fn details(meta: &GridMeta) -> String {
+    let mut line = Vec::new();
+    if let Some(difficulty) = meta.difficulty {
+        line.push(format!("{difficulty:?}"));
+    }
+    if !meta.author.is_empty() {
+        line.push(format!("by {}", meta.author));
+    }
+    if !meta.created.is_empty() {
+        line.push(meta.created.clone());
+    }
+
+    let mut details = line.join(" - ");
+    if !meta.description.is_empty() {
+        details.push('\n');
+        details.push_str(&meta.description);
+    }
+    details
+}
+
+//the user levels, in a column right of the campaign
+fn spawn_user_levels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    levels: Res<UserLevels>,
+) {
+    let font = |size: f32| TextFont {
+        font: asset_server.load("fonts/courbd.ttf"),
+        font_size: size,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Name::new("user levels"),
+            LevelsMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                right: Val::Px(20.0),
+                width: Val::Px(360.0),
+                max_height: Val::Percent(90.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("YOUR LEVELS"),
+                font(24.0),
+                TextColor(Color::srgb(0.5, 0.0, 0.0)),
+            ));
+            if levels.0.is_empty() {
+                parent.spawn((
+                    Text::new(format!(
+                        "put .grid.json files in {}",
+                        user_levels_dir().display()
+                    )),
+                    font(14.0),
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+                return;
+            }
+
+            parent
+                .spawn((
+                    UserLevelList,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    for (i, level) in levels.0.iter().enumerate() {
+                        let (details, color) = match &level.state {
+                            Ok(state) => (details(&state.meta), LEVEL_BUTTON),
+                            Err(e) => (format!("can't be played: {e}"), BROKEN_LEVEL),
+                        };
+                        list.spawn((
+                            Button,
+                            ButtonType::UserLevel(i),
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                border: UiRect::all(Val::Px(3.0)),
+                                padding: UiRect::all(Val::Px(6.0)),
+                                flex_shrink: 0.,
+                                ..default()
+                            },
+                            BorderColor(Color::BLACK),
+                            BackgroundColor(color),
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new(level.title()),
+                                font(20.0),
+                                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                            ));
+                            if !details.is_empty() {
+                                button.spawn((
+                                    Text::new(details),
+                                    font(13.0),
+                                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                                ));
+                            }
+                        });
+                    }
+                });
+        });
+}
@@ -1,29 +1,46 @@
 use bevy::prelude::*;
 
+use balance::BalancePlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use camera::CameraPlugin;
 use defeat_screen::DefeatScreenPlugin;
+use editor::EditorPlugin;
 use game::GamePlugin;
 use grid::GridPlugin;
 use health::HealthPlugin;
+use history::HistoryPlugin;
 use items::ItemsPlugin;
 use levels_menu::LevelsPlugin;
 use main_menu::MainMenuPlugin;
+use profiles::ProfilesPlugin;
+use savegame::SaveGamePlugin;
+use score::ScorePlugin;
 use shake::ShakePlugin;
 use shop::ShopPlugin;
+use toast::ToastPlugin;
 use victory_screen::VictoryScreenPlugin;
 
+pub mod balance;
 pub mod camera;
+pub mod clipboard;
 pub mod defeat_screen;
+pub mod editor;
+pub mod encoding;
 pub mod game;
 pub mod grid;
 pub mod health;
+pub mod history;
 pub mod items;
 pub mod levels;
 pub mod levels_menu;
 pub mod main_menu;
+pub mod profiles;
+pub mod savegame;
+pub mod score;
 pub mod shake;
 pub mod shop;
+pub mod toast;
+pub mod user_data;
 pub mod victory_screen;
 
 #[cfg(test)]
@@ -32,10 +49,20 @@ pub mod testing;
 //nothing much to say, just implementing all the plugins and modules created
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                // hot reloads balance.json while playing
+                .set(AssetPlugin {
+                    watch_for_changes_override: Some(true),
+                    ..Default::default()
+                }),
+        )
         .add_plugins(WorldInspectorPlugin::new())
+        .add_plugins((ToastPlugin, ProfilesPlugin, ScorePlugin))
         .add_plugins((
             CameraPlugin,
+            BalancePlugin,
             GamePlugin,
             GridPlugin,
             ShopPlugin,
@@ -46,6 +73,9 @@ fn main() {
             ShakePlugin,
             DefeatScreenPlugin,
             VictoryScreenPlugin,
+            EditorPlugin,
+            HistoryPlugin,
+            SaveGamePlugin,
         ))
         .run();
 }
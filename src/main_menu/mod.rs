@@ -1,7 +1,11 @@
 use bevy::prelude::*;
-use ui::{LevelsBtnPress, PlayBtnPress, QuitBtnPress, UIPlugin};
+use ui::{ContinueBtnPress, EditorBtnPress, LevelsBtnPress, PlayBtnPress, QuitBtnPress, UIPlugin};
 
-use crate::game::{GameLevels, GameStates};
+use crate::{
+    game::{EditorStates, GameLevels, GameStates},
+    savegame::{ResumeGame, SaveGame},
+    toast::Toast,
+};
 
 pub mod ui;
 
@@ -15,11 +19,39 @@ impl Plugin for MainMenuPlugin {
         app.add_plugins(UIPlugin);
         app.add_systems(
             Update,
-            (on_play, on_levels, on_quit).run_if(in_state(GameStates::MainMenu)),
+            (on_continue, on_play, on_levels, on_editor, on_quit)
+                .run_if(in_state(GameStates::MainMenu)),
         );
     }
 }
 
+//picks the level left halfway through back up
+fn on_continue(
+    mut event: EventReader<ContinueBtnPress>,
+    mut next_state: ResMut<NextState<GameStates>>,
+    mut commands: Commands,
+    main_menu: Query<Entity, With<MainMenu>>,
+    mut next_level: ResMut<NextState<GameLevels>>,
+    mut toasts: EventWriter<Toast>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+    let save = match SaveGame::read() {
+        Ok(save) => save,
+        Err(e) => {
+            toasts.send(Toast(format!("couldn't continue the saved game: {e}")));
+            return;
+        }
+    };
+    let main_menu = main_menu.single();
+    commands.entity(main_menu).despawn_recursive();
+
+    next_level.set(save.level);
+    commands.insert_resource(ResumeGame(Some(save)));
+    next_state.set(GameStates::InGame);
+}
+
 //sends you to sandbox
 fn on_play(
     mut event: EventReader<PlayBtnPress>,
@@ -55,6 +87,24 @@ fn on_levels(
     next_state.set(GameStates::LevelsMenu);
 }
 
+//opens the level editor on an empty grid
+fn on_editor(
+    mut event: EventReader<EditorBtnPress>,
+    mut next_state: ResMut<NextState<GameStates>>,
+    mut next_editor: ResMut<NextState<EditorStates>>,
+    mut commands: Commands,
+    main_menu: Query<Entity, With<MainMenu>>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+    let main_menu = main_menu.single();
+    commands.entity(main_menu).despawn_recursive();
+
+    next_editor.set(EditorStates::Editing);
+    next_state.set(GameStates::InGame);
+}
+
 //exits the game
 fn on_quit(mut event: EventReader<QuitBtnPress>, mut exit: EventWriter<AppExit>) {
     if event.read().len() == 0 {
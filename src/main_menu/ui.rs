@@ -1,7 +1,7 @@
 use bevy::color::palettes::basic::*;
 use bevy::prelude::*;
 
-use crate::game::GameStates;
+use crate::{game::GameStates, savegame::SaveGame};
 
 use super::MainMenu;
 
@@ -9,19 +9,27 @@ const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
 
+#[derive(Event)]
+pub struct ContinueBtnPress;
+
 #[derive(Event)]
 pub struct PlayBtnPress;
 
 #[derive(Event)]
 pub struct LevelsBtnPress;
 
+#[derive(Event)]
+pub struct EditorBtnPress;
+
 #[derive(Event)]
 pub struct QuitBtnPress;
 
 #[derive(Component)]
 pub enum ButtonType {
+    Continue,
     Play,
     Levels,
+    Editor,
     Quit,
 }
 
@@ -29,8 +37,10 @@ pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<ContinueBtnPress>();
         app.add_event::<PlayBtnPress>();
         app.add_event::<LevelsBtnPress>();
+        app.add_event::<EditorBtnPress>();
         app.add_event::<QuitBtnPress>();
         app.add_systems(OnEnter(GameStates::MainMenu), setup);
         app.add_systems(Update, button_system.run_if(in_state(GameStates::MainMenu)));
@@ -49,8 +59,10 @@ fn button_system(
         ),
         Changed<Interaction>,
     >,
+    mut continue_game: EventWriter<ContinueBtnPress>,
     mut play: EventWriter<PlayBtnPress>,
     mut levels: EventWriter<LevelsBtnPress>,
+    mut editor: EventWriter<EditorBtnPress>,
     mut quit: EventWriter<QuitBtnPress>,
 ) {
     for (interaction, btn_type, mut color, mut border_color, _children) in &mut interaction_query {
@@ -60,12 +72,18 @@ fn button_system(
                 border_color.0 = RED.into();
 
                 match btn_type {
+                    ButtonType::Continue => {
+                        continue_game.send(ContinueBtnPress);
+                    }
                     ButtonType::Play => {
                         play.send(PlayBtnPress);
                     }
                     ButtonType::Levels => {
                         levels.send(LevelsBtnPress);
                     }
+                    ButtonType::Editor => {
+                        editor.send(EditorBtnPress);
+                    }
                     ButtonType::Quit => {
                         quit.send(QuitBtnPress);
                     }
@@ -109,6 +127,35 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 },
                 TextColor(Color::srgb(0.5, 0.0, 0.0)),
             ));
+            // only when a level was left halfway through
+            if SaveGame::exists() {
+                parent
+                    .spawn((
+                        Button,
+                        ButtonType::Continue,
+                        Node {
+                            width: Val::Px(250.0),
+                            height: Val::Px(65.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            // horizontally center child text
+                            justify_content: JustifyContent::Center,
+                            // vertically center child text
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BorderColor(Color::BLACK),
+                        BackgroundColor(NORMAL_BUTTON),
+                    ))
+                    .with_child((
+                        Text::new("Continue"),
+                        TextFont {
+                            font: asset_server.load("fonts/courbd.ttf"),
+                            font_size: 33.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+            }
             parent
                 .spawn((
                     Button,
@@ -161,6 +208,32 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     },
                     TextColor(Color::srgb(0.9, 0.9, 0.9)),
                 ));
+            parent
+                .spawn((
+                    Button,
+                    ButtonType::Editor,
+                    Node {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(5.0)),
+                        // horizontally center child text
+                        justify_content: JustifyContent::Center,
+                        // vertically center child text
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::BLACK),
+                    BackgroundColor(NORMAL_BUTTON),
+                ))
+                .with_child((
+                    Text::new("Editor"),
+                    TextFont {
+                        font: asset_server.load("fonts/courbd.ttf"),
+                        font_size: 33.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                ));
             parent
                 .spawn((
                     Button,
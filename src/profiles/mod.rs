@@ -0,0 +1,505 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use ui::{
+    DeleteProfileBtnPress, NewProfileBtnPress, NextProfileBtnPress, PrevProfileBtnPress,
+    ProfileMenu, ProfileUIPlugin, ShakeBtnPress,
+};
+
+use crate::{
+    game::{GameLevels, GameStates, HighestLevel, despawn_game},
+    grid::{format::today, save_load::CustomLevel},
+    health::Health,
+    score::{LeaderboardEntry, RunScore, RunStats, Score, place_on_leaderboard},
+    shop::currency::Currency,
+    toast::Toast,
+    user_data::{new_file_path, profiles_dir},
+};
+
+pub mod ui;
+
+// changes whenever the profile file does, newer profiles are left alone
+pub const PROFILE_VERSION: u32 = 1;
+const PROFILE_EXTENSION: &str = "profile.json";
+// holds the file name of the profile played last
+const CURRENT_PROFILE_FILE: &str = "current";
+const DEFAULT_PROFILE_NAME: &str = "Player";
+// where progress was kept before profiles, read once on the first launch
+pub const HIGHEST_LEVEL_PATH: &str = "assets/highestlvl";
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Newer(u32),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "{e}"),
+            ProfileError::Json(e) => write!(f, "{e}"),
+            ProfileError::Newer(version) => write!(
+                f,
+                "the profile is version {version}, this game only reads up to {PROFILE_VERSION}"
+            ),
+        }
+    }
+}
+
+// how a level was beaten
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LevelResult {
+    pub health: i32,
+    pub currency: i32,
+    pub seconds: f32,
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl LevelResult {
+    //the higher score wins, then the faster run
+    pub fn better_than(&self, other: &LevelResult) -> bool {
+        self.score > other.score || (self.score == other.score && self.seconds < other.seconds)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub screen_shake: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { screen_shake: true }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Stats {
+    pub victories: u32,
+    pub defeats: u32,
+    pub play_seconds: f32,
+}
+
+// everything kept for one player
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Profile {
+    pub version: u32,
+    pub name: String,
+    // last campaign level beaten, the one after it is unlocked
+    pub highest: GameLevels,
+    // best result of each level beaten, by level_key
+    #[serde(default)]
+    pub best: BTreeMap<String, LevelResult>,
+    // best runs of each level, highest score first
+    #[serde(default)]
+    pub leaderboards: BTreeMap<String, Vec<LeaderboardEntry>>,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub stats: Stats,
+    // file the profile is kept in
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+// only the version, read before the rest in case the layout changed
+#[derive(serde::Deserialize)]
+struct ProfileVersion {
+    version: u32,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Profile {
+        Profile {
+            version: PROFILE_VERSION,
+            name: name.to_string(),
+            highest: GameLevels::Easy,
+            best: BTreeMap::new(),
+            leaderboards: BTreeMap::new(),
+            settings: default(),
+            stats: default(),
+            path: PathBuf::new(),
+        }
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Profile, ProfileError> {
+        let version: ProfileVersion = serde_json::from_slice(bytes).map_err(ProfileError::Json)?;
+        if version.version > PROFILE_VERSION {
+            return Err(ProfileError::Newer(version.version));
+        }
+        serde_json::from_slice(bytes).map_err(ProfileError::Json)
+    }
+
+    pub fn read(path: &Path) -> Result<Profile, ProfileError> {
+        let bytes = fs::read(path).map_err(ProfileError::Io)?;
+        let mut profile = Profile::parse(&bytes)?;
+        profile.version = PROFILE_VERSION;
+        profile.path = path.to_path_buf();
+        Ok(profile)
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(&self.path, json)
+    }
+
+    //keeps the result if it's the best one of the level
+    pub fn record(&mut self, key: String, result: LevelResult) {
+        match self.best.get(&key) {
+            Some(best) if !result.better_than(best) => {}
+            _ => {
+                self.best.insert(key, result);
+            }
+        }
+    }
+}
+
+//the name results of a level are kept under, sandbox has none
+pub fn level_key(level: GameLevels, custom: &CustomLevel) -> Option<String> {
+    match level {
+        GameLevels::Sandbox => None,
+        GameLevels::Custom => custom
+            .0
+            .as_ref()
+            .filter(|state| !state.meta.title.is_empty())
+            .map(|state| format!("custom: {}", state.meta.title)),
+        _ => Some(format!("{level:?}")),
+    }
+}
+
+// every profile that could be read, and the one being played
+#[derive(Resource)]
+pub struct Profiles {
+    pub list: Vec<Profile>,
+    pub current: usize,
+}
+
+impl Profiles {
+    pub fn current(&self) -> &Profile {
+        &self.list[self.current]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Profile {
+        &mut self.list[self.current]
+    }
+
+    //remembers the profile for the next launch
+    pub fn select(&mut self, index: usize) -> io::Result<()> {
+        self.current = index.min(self.list.len() - 1);
+        let file_name = self.current().path.file_name().unwrap_or_default();
+        fs::write(
+            profiles_dir().join(CURRENT_PROFILE_FILE),
+            file_name.as_encoded_bytes(),
+        )
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("the profile needs a name".to_string());
+        }
+        if self.list.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+            return Err(format!("there's already a profile called {name}"));
+        }
+
+        let mut profile = Profile::new(name);
+        profile.path = new_file_path(&profiles_dir(), name, PROFILE_EXTENSION);
+        profile.write().map_err(|e| e.to_string())?;
+        self.list.push(profile);
+        self.select(self.list.len() - 1).map_err(|e| e.to_string())
+    }
+
+    //the last profile can't be deleted, there'd be nothing to play with
+    pub fn delete_current(&mut self) -> Result<(), String> {
+        if self.list.len() == 1 {
+            return Err("the only profile can't be deleted".to_string());
+        }
+        fs::remove_file(&self.current().path).map_err(|e| e.to_string())?;
+        self.list.remove(self.current);
+        self.select(self.current).map_err(|e| e.to_string())
+    }
+}
+
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ProfileUIPlugin);
+        app.add_systems(Startup, load_profiles);
+        app.add_systems(
+            Update,
+            (on_switch, on_new, type_name, on_delete, on_shake)
+                .run_if(in_state(GameStates::MainMenu)),
+        );
+        app.add_systems(Update, sync_highest.run_if(resource_changed::<Profiles>));
+        app.add_systems(OnExit(GameStates::InGame), finish_run.before(despawn_game));
+    }
+}
+
+//reads every profile, the first launch makes one from the old progress file
+fn load_profiles(mut commands: Commands, mut toasts: EventWriter<Toast>) {
+    let dir = profiles_dir();
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.to_string_lossy().ends_with(PROFILE_EXTENSION))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    let first_launch = paths.is_empty();
+
+    let mut list = Vec::new();
+    for path in paths {
+        match Profile::read(&path) {
+            Ok(profile) => list.push(profile),
+            Err(e) => {
+                toasts.send(Toast(format!(
+                    "couldn't read the profile {}: {e}",
+                    path.display()
+                )));
+            }
+        }
+    }
+
+    if list.is_empty() {
+        let mut profile = Profile::new(DEFAULT_PROFILE_NAME);
+        profile.path = new_file_path(&dir, DEFAULT_PROFILE_NAME, PROFILE_EXTENSION);
+        if first_launch {
+            import_highest_level(&mut profile, &mut toasts);
+        }
+        if let Err(e) = profile.write() {
+            toasts.send(Toast(format!("couldn't save the profile: {e}")));
+        }
+        list.push(profile);
+    }
+
+    let last = fs::read(dir.join(CURRENT_PROFILE_FILE)).unwrap_or_default();
+    let current = list
+        .iter()
+        .position(|p| {
+            p.path
+                .file_name()
+                .is_some_and(|name| name.as_encoded_bytes() == last.as_slice())
+        })
+        .unwrap_or(0);
+    info!("playing as {}", list[current].name);
+    commands.insert_resource(Profiles { list, current });
+}
+
+//progress from before profiles, a single byte with the highest level beaten
+fn import_highest_level(profile: &mut Profile, toasts: &mut EventWriter<Toast>) {
+    let Ok(contents) = fs::read(HIGHEST_LEVEL_PATH) else {
+        return;
+    };
+    // custom levels are never written there
+    match contents.first().map(|byte| GameLevels::try_from(*byte)) {
+        Some(Ok(level)) if level != GameLevels::Custom => profile.highest = level,
+        _ => {
+            toasts.send(Toast(format!(
+                "{HIGHEST_LEVEL_PATH} is corrupted, progress starts from the first level"
+            )));
+        }
+    }
+}
+
+//goes through the profiles, wrapping around
+fn on_switch(
+    mut prev: EventReader<PrevProfileBtnPress>,
+    mut next: EventReader<NextProfileBtnPress>,
+    mut profiles: ResMut<Profiles>,
+    mut menu: ResMut<ProfileMenu>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let step = next.read().len() as isize - prev.read().len() as isize;
+    if step == 0 {
+        return;
+    }
+
+    let count = profiles.list.len() as isize;
+    let index = (profiles.current as isize + step).rem_euclid(count) as usize;
+    if let Err(e) = profiles.select(index) {
+        toasts.send(Toast(format!("couldn't remember the profile: {e}")));
+    }
+    *menu = ProfileMenu::default();
+}
+
+fn on_new(mut event: EventReader<NewProfileBtnPress>, mut menu: ResMut<ProfileMenu>) {
+    if event.read().len() == 0 {
+        return;
+    }
+    *menu = ProfileMenu {
+        naming: Some(String::new()),
+        status: "type a name, Enter creates the profile and Escape cancels".to_string(),
+        ..default()
+    };
+}
+
+//typing the name of a new profile
+fn type_name(
+    mut events: EventReader<KeyboardInput>,
+    mut menu: ResMut<ProfileMenu>,
+    mut profiles: ResMut<Profiles>,
+) {
+    if menu.naming.is_none() {
+        events.clear();
+        return;
+    }
+
+    for ev in events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some(name) = menu.naming.as_mut() else {
+            continue;
+        };
+        match &ev.logical_key {
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Space => name.push(' '),
+            Key::Character(text) => name.push_str(text),
+            Key::Escape => *menu = ProfileMenu::default(),
+            Key::Enter => {
+                let name = name.clone();
+                menu.status = match profiles.create(&name) {
+                    Ok(()) => {
+                        menu.naming = None;
+                        format!("created {}", name.trim())
+                    }
+                    Err(e) => e,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+//the first press asks, the second one deletes
+fn on_delete(
+    mut event: EventReader<DeleteProfileBtnPress>,
+    mut menu: ResMut<ProfileMenu>,
+    mut profiles: ResMut<Profiles>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+    let name = profiles.current().name.clone();
+    if !menu.confirm_delete {
+        *menu = ProfileMenu {
+            confirm_delete: true,
+            status: format!("press DELETE again to delete {name} and its progress"),
+            ..default()
+        };
+        return;
+    }
+
+    menu.confirm_delete = false;
+    menu.status = match profiles.delete_current() {
+        Ok(()) => format!("deleted {name}"),
+        Err(e) => e,
+    };
+}
+
+fn on_shake(
+    mut event: EventReader<ShakeBtnPress>,
+    mut profiles: ResMut<Profiles>,
+    mut toasts: EventWriter<Toast>,
+) {
+    if event.read().len() == 0 {
+        return;
+    }
+    let settings = &mut profiles.current_mut().settings;
+    settings.screen_shake = !settings.screen_shake;
+
+    if let Err(e) = profiles.current().write() {
+        toasts.send(Toast(format!("couldn't save the profile: {e}")));
+    }
+}
+
+//the levels menu unlocks from the profile being played
+fn sync_highest(profiles: Res<Profiles>, mut highest: ResMut<HighestLevel>) {
+    highest.highest = profiles.current().highest;
+}
+
+//keeps what the level that's over left on the profile, before the game is reset
+fn finish_run(
+    state: Res<State<GameStates>>,
+    level: Res<State<GameLevels>>,
+    custom: Res<CustomLevel>,
+    health: Res<Health>,
+    currency: Res<Currency>,
+    mut run: ResMut<RunStats>,
+    mut profiles: ResMut<Profiles>,
+    mut commands: Commands,
+    mut toasts: EventWriter<Toast>,
+) {
+    let level = **level;
+    let profile = profiles.current_mut();
+    profile.stats.play_seconds += run.session_seconds;
+
+    match state.get() {
+        GameStates::VictoryScreen => {
+            profile.stats.victories += 1;
+            let score = Score::new(health.value, currency.value, &run);
+            let mut run_score = RunScore {
+                score,
+                seconds: run.seconds,
+                packets_destroyed: run.packets_destroyed,
+                place: None,
+                leaderboard: Vec::new(),
+            };
+
+            if let Some(key) = level_key(level, &custom) {
+                let result = LevelResult {
+                    health: health.value,
+                    currency: currency.value,
+                    seconds: run.seconds,
+                    score: score.total(),
+                };
+                profile.record(key.clone(), result);
+
+                let entry = LeaderboardEntry {
+                    name: profile.name.clone(),
+                    score: score.total(),
+                    date: today(),
+                    run_id: run.run_id,
+                };
+                let leaderboard = profile.leaderboards.entry(key).or_default();
+                run_score.place = place_on_leaderboard(leaderboard, entry);
+                run_score.leaderboard = leaderboard.clone();
+            }
+            commands.insert_resource(run_score);
+
+            // custom levels aren't part of the campaign
+            if level != GameLevels::Custom && u8::from(level) > u8::from(profile.highest) {
+                profile.highest = level;
+            }
+        }
+        GameStates::DefeatScreen => profile.stats.defeats += 1,
+        _ => {}
+    }
+    *run = RunStats::default();
+
+    if let Err(e) = profiles.current().write() {
+        toasts.send(Toast(format!("couldn't save your progress: {e}")));
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::color::palettes::basic::*;
+use bevy::prelude::*;
+
+use crate::game::GameStates;
+
+use super::Profiles;
+
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
+
+#[derive(Event)]
+pub struct PrevProfileBtnPress;
+
+#[derive(Event)]
+pub struct NextProfileBtnPress;
+
+#[derive(Event)]
+pub struct NewProfileBtnPress;
+
+#[derive(Event)]
+pub struct DeleteProfileBtnPress;
+
+#[derive(Event)]
+pub struct ShakeBtnPress;
+
+#[derive(Component)]
+pub enum ButtonType {
+    Prev,
+    Next,
+    New,
+    Delete,
+    Shake,
+}
+
+// what the profile panel of the main menu is in the middle of
+#[derive(Resource, Default)]
+pub struct ProfileMenu {
+    // the name typed for a new profile
+    pub naming: Option<String>,
+    // delete was pressed once, the next press deletes
+    pub confirm_delete: bool,
+    pub status: String,
+}
+
+#[derive(Component)]
+struct ProfilePanel;
+
+#[derive(Component, Clone, Copy)]
+enum ProfileText {
+    Name,
+    Stats,
+    Shake,
+    Status,
+}
+
+pub struct ProfileUIPlugin;
+
+impl Plugin for ProfileUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PrevProfileBtnPress>();
+        app.add_event::<NextProfileBtnPress>();
+        app.add_event::<NewProfileBtnPress>();
+        app.add_event::<DeleteProfileBtnPress>();
+        app.add_event::<ShakeBtnPress>();
+        app.init_resource::<ProfileMenu>();
+        app.add_systems(OnEnter(GameStates::MainMenu), setup);
+        app.add_systems(OnExit(GameStates::MainMenu), despawn_panel);
+        app.add_systems(
+            Update,
+            (button_system, update_panel).run_if(in_state(GameStates::MainMenu)),
+        );
+    }
+}
+
+//manages interactions
+fn button_system(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &ButtonType,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut prev: EventWriter<PrevProfileBtnPress>,
+    mut next: EventWriter<NextProfileBtnPress>,
+    mut new: EventWriter<NewProfileBtnPress>,
+    mut delete: EventWriter<DeleteProfileBtnPress>,
+    mut shake: EventWriter<ShakeBtnPress>,
+) {
+    for (interaction, btn_type, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                border_color.0 = RED.into();
+
+                match btn_type {
+                    ButtonType::Prev => {
+                        prev.send(PrevProfileBtnPress);
+                    }
+                    ButtonType::Next => {
+                        next.send(NextProfileBtnPress);
+                    }
+                    ButtonType::New => {
+                        new.send(NewProfileBtnPress);
+                    }
+                    ButtonType::Delete => {
+                        delete.send(DeleteProfileBtnPress);
+                    }
+                    ButtonType::Shake => {
+                        shake.send(ShakeBtnPress);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+
+//what a text of the panel says
+fn panel_text(text: ProfileText, profiles: &Profiles, menu: &ProfileMenu) -> String {
+    let profile = profiles.current();
+    match text {
+        ProfileText::Name => match &menu.naming {
+            Some(name) => format!("New profile: {name}_"),
+            None => format!("Profile: {}", profile.name),
+        },
+        ProfileText::Stats => format!(
+            "{} won - {} lost - {} min played",
+            profile.stats.victories,
+            profile.stats.defeats,
+            (profile.stats.play_seconds / 60.) as u32
+        ),
+        ProfileText::Shake => match profile.settings.screen_shake {
+            true => "Shake: on".to_string(),
+            false => "Shake: off".to_string(),
+        },
+        ProfileText::Status => menu.status.clone(),
+    }
+}
+
+fn update_panel(
+    profiles: Res<Profiles>,
+    menu: Res<ProfileMenu>,
+    mut texts: Query<(&mut Text, &ProfileText)>,
+) {
+    if !profiles.is_changed() && !menu.is_changed() {
+        return;
+    }
+
+    for (mut text, profile_text) in &mut texts {
+        text.0 = panel_text(*profile_text, &profiles, &menu);
+    }
+}
+
+fn despawn_panel(mut commands: Commands, panel: Query<Entity, With<ProfilePanel>>) {
+    for panel in &panel {
+        commands.entity(panel).despawn_recursive();
+    }
+}
+
+//the profile being played and what can be done with it, in the top left corner
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    profiles: Res<Profiles>,
+    mut menu: ResMut<ProfileMenu>,
+) {
+    *menu = ProfileMenu::default();
+
+    let font = |size: f32| TextFont {
+        font: asset_server.load("fonts/courbd.ttf"),
+        font_size: size,
+        ..default()
+    };
+    let button = Node {
+        height: Val::Px(40.0),
+        padding: UiRect::axes(Val::Px(10.0), Val::Px(0.0)),
+        border: UiRect::all(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let text = |profile_text: ProfileText, size: f32| {
+        (
+            profile_text,
+            Text::new(panel_text(profile_text, &profiles, &menu)),
+            font(size),
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        )
+    };
+
+    commands
+        .spawn((
+            Name::new("profile panel"),
+            ProfilePanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Px(20.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(text(ProfileText::Name, 22.0));
+            parent.spawn(text(ProfileText::Stats, 14.0));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (btn_type, label) in [
+                        (ButtonType::Prev, "<"),
+                        (ButtonType::Next, ">"),
+                        (ButtonType::New, "NEW"),
+                        (ButtonType::Delete, "DELETE"),
+                    ] {
+                        row.spawn((
+                            Button,
+                            btn_type,
+                            button.clone(),
+                            BorderColor(Color::BLACK),
+                            BackgroundColor(NORMAL_BUTTON),
+                        ))
+                        .with_child((
+                            Text::new(label),
+                            font(16.0),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                    }
+                });
+            parent
+                .spawn((
+                    Button,
+                    ButtonType::Shake,
+                    Node {
+                        align_self: AlignSelf::Start,
+                        ..button.clone()
+                    },
+                    BorderColor(Color::BLACK),
+                    BackgroundColor(NORMAL_BUTTON),
+                ))
+                .with_child(text(ProfileText::Shake, 16.0));
+            parent.spawn((
+                ProfileText::Status,
+                Text::new(""),
+                font(14.0),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
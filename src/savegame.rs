@@ -0,0 +1,415 @@
+use std::{fs, path::Path, time::Duration};
+
+use bevy::{prelude::*, window::WindowCloseRequested};
+
+use crate::{
+    encoding,
+    game::{EditorStates, GameLevels, GameStates},
+    grid::{
+        Grid,
+        save_load::{GridState, build_grid},
+        terrain::Locked,
+    },
+    health::{Health, PcHealth, init_health},
+    items::{
+        antivirus::ScanRate,
+        cables::Cable,
+        honeypots::HeldPackets,
+        packets::{EnemyPacket, Packet, PacketType, PlayerPacket},
+        pcs::Destroyed,
+        projectiles::{Projectile, SavedProjectile},
+        registry::ItemRegistry,
+        servers::{FireRate, ServerOutput},
+        upgrades::{RestoreLevel, UpgradeLevel},
+        ups::Regen,
+    },
+    levels::{SavedWaves, WaveManager, get_level},
+    score::RunStats,
+    shop::{
+        currency::{Currency, init_currency},
+        shop_items::ItemType,
+    },
+    toast::Toast,
+};
+
+pub const SAVE_GAME_PATH: &str = "assets/savegame.bin";
+// changes whenever the save game or the grid state it holds does, older saves can't be continued
+const SAVE_GAME_VERSION: u32 = 5;
+
+// a level left halfway through, picked up again from the main menu
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SaveGame {
+    pub level: GameLevels,
+    grid: GridState,
+    items: Vec<SavedItem>,
+    packets: Vec<SavedPacket>,
+    projectiles: Vec<SavedProjectile>,
+    health: i32,
+    currency: i32,
+    waves: Option<SavedWaves>,
+    run: RunStats,
+}
+
+impl SaveGame {
+    pub fn exists() -> bool {
+        Path::new(SAVE_GAME_PATH).exists()
+    }
+
+    pub fn read() -> std::io::Result<SaveGame> {
+        encoding::read_file(SAVE_GAME_PATH, SAVE_GAME_VERSION)
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        encoding::write_file(SAVE_GAME_PATH, self, SAVE_GAME_VERSION)
+    }
+}
+
+// what an item placed on the grid went through, on top of the grid file
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct SavedItem {
+    cell: UVec2,
+    level: u32,
+    health: Option<SavedHealth>,
+    held: Option<Vec<i32>>,
+    output: Option<SavedOutput>,
+    timers: SavedTimers,
+}
+
+// how far along the timers of an item were, so it fires and scans on the same beat
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+struct SavedTimers {
+    fire_rate: Option<f32>,
+    // packets left in a burst and the time since the last one
+    burst: Option<(u32, f32)>,
+    scan_rate: Option<f32>,
+    regen_stored: Option<f32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct SavedHealth {
+    value: i32,
+    since_damage: f32,
+    regenerating: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct SavedOutput {
+    ports: [bool; 4],
+    packet_type: PacketType,
+    burst: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedPacket {
+    pos: Vec3,
+    dir: Vec2,
+    hp: i32,
+    packet_type: PacketType,
+    dmg_multi: i32,
+    enemy: bool,
+}
+
+// set by the Continue button, the level starts from it instead of its grid file
+#[derive(Resource)]
+pub struct ResumeGame(pub Option<SaveGame>);
+
+// an item spawned again from a save game, waiting for its components to be restored
+#[derive(Component)]
+struct Resumed(SavedItem);
+
+// set once the item went through its upgrades again, which start its timers over
+#[derive(Component)]
+struct ResumedTimers(SavedTimers);
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameStates::InGame),
+            resume_game.before(init_health).before(init_currency),
+        );
+        app.add_systems(
+            Update,
+            (
+                save_on_leave.run_if(in_state(EditorStates::Playing)),
+                restore_items,
+            )
+                .run_if(in_state(GameStates::InGame)),
+        );
+        // after the items' own systems, which set their timers when spawned or upgraded
+        app.add_systems(
+            PostUpdate,
+            restore_timers.run_if(in_state(GameStates::InGame)),
+        );
+        app.add_systems(OnEnter(GameStates::VictoryScreen), delete_save_game);
+        app.add_systems(OnEnter(GameStates::DefeatScreen), delete_save_game);
+    }
+}
+
+//leaving a level with Escape or closing the game saves it
+fn save_on_leave(
+    keys: Res<ButtonInput<KeyCode>>,
+    (mut close, mut exit): (EventReader<WindowCloseRequested>, EventReader<AppExit>),
+    grid: Res<Grid>,
+    item_types: Query<&ItemType>,
+    cables: Query<&Cable>,
+    locked: Query<(), With<Locked>>,
+    items: Query<(
+        Option<&UpgradeLevel>,
+        Option<&PcHealth>,
+        Option<&HeldPackets>,
+        Option<&ServerOutput>,
+        Option<&FireRate>,
+        Option<&ScanRate>,
+        Option<&Regen>,
+    )>,
+    packets: Query<(Entity, &Transform, &Packet, Has<EnemyPacket>)>,
+    projectiles: Query<(&Transform, &Projectile)>,
+    registry: Res<ItemRegistry>,
+    health: Res<Health>,
+    currency: Res<Currency>,
+    wave_manager: Res<WaveManager>,
+    level: Res<State<GameLevels>>,
+    run: Res<RunStats>,
+    mut toasts: EventWriter<Toast>,
+) {
+    // both readers are drained, so an event isn't seen again next frame
+    let closing = close.read().count() + exit.read().count() > 0;
+    if !keys.just_pressed(KeyCode::Escape) && !closing {
+        return;
+    }
+
+    let packet_order: Vec<Entity> = packets.iter().map(|(e, ..)| e).collect();
+    let index = |entity: Entity| packet_order.iter().position(|e| *e == entity);
+
+    let save = SaveGame {
+        level: **level,
+        grid: GridState::from_grid(&grid, &item_types, &cables, &locked, &registry),
+        items: grid
+            .entities()
+            .filter(|(entity, _)| !cables.contains(*entity))
+            .filter_map(|(entity, _)| {
+                let (level, pc_health, held, output, fire_rate, scan_rate, regen) =
+                    items.get(entity).ok()?;
+                Some(SavedItem {
+                    cell: grid.bounds(entity)?.min,
+                    level: level.map_or(0, |l| l.level),
+                    health: pc_health.map(|h| SavedHealth {
+                        value: h.value,
+                        since_damage: h.since_damage,
+                        regenerating: h.regenerating,
+                    }),
+                    held: held.map(|h| h.held.clone()),
+                    output: output.map(|o| SavedOutput {
+                        ports: o.ports,
+                        packet_type: o.packet_type,
+                        burst: o.burst,
+                    }),
+                    timers: SavedTimers {
+                        fire_rate: fire_rate.map(|f| f.0.elapsed_secs()),
+                        burst: output.map(|o| (o.burst_left, o.burst_timer.elapsed_secs())),
+                        scan_rate: scan_rate.map(|s| s.0.elapsed_secs()),
+                        regen_stored: regen.map(|r| r.stored),
+                    },
+                })
+            })
+            .collect(),
+        packets: packets
+            .iter()
+            .map(|(_, transform, packet, enemy)| SavedPacket {
+                pos: transform.translation,
+                dir: packet.dir,
+                hp: packet.hp,
+                packet_type: packet.packet_type,
+                dmg_multi: packet.dmg_multi,
+                enemy,
+            })
+            .collect(),
+        projectiles: projectiles
+            .iter()
+            .map(|(transform, projectile)| projectile.save(transform.translation, index))
+            .collect(),
+        health: health.value,
+        currency: currency.value,
+        waves: wave_manager.save(),
+        run: run.clone(),
+    };
+
+    info!("saving the game");
+    if let Err(e) = save.write() {
+        toasts.send(Toast(format!("couldn't save the game: {e}")));
+    }
+}
+
+//lays the saved level out again, with everything that was moving on it
+fn resume_game(
+    resume: Option<ResMut<ResumeGame>>,
+    mut grid: ResMut<Grid>,
+    registry: Res<ItemRegistry>,
+    asset_server: Res<AssetServer>,
+    mut health: ResMut<Health>,
+    mut currency: ResMut<Currency>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut run: ResMut<RunStats>,
+    mut commands: Commands,
+) {
+    let Some(save) = resume.and_then(|mut r| r.0.take()) else {
+        return;
+    };
+    commands.remove_resource::<ResumeGame>();
+
+    build_grid(
+        save.grid,
+        &mut grid,
+        &registry,
+        &asset_server,
+        &mut commands,
+    );
+
+    for item in save.items {
+        let Some(entity) = grid.at(item.cell) else {
+            warn!("no saved item at {}", item.cell);
+            continue;
+        };
+        commands.entity(entity).insert(Resumed(item));
+    }
+
+    let packets: Vec<Entity> = save
+        .packets
+        .iter()
+        .map(|saved| {
+            let mut packet = Packet::new(saved.dir, saved.packet_type, &default());
+            packet.hp = saved.hp;
+            packet.dmg_multi = saved.dmg_multi;
+
+            let transform = Transform::from_translation(saved.pos);
+            match saved.enemy {
+                true => commands.spawn((
+                    EnemyPacket,
+                    packet,
+                    Sprite::from_image(asset_server.load("enemy_packet.png")),
+                    transform,
+                    Name::from("Enemy packet"),
+                )),
+                false => {
+                    let mut sprite = Sprite::from_image(asset_server.load("player_packet.png"));
+                    if saved.packet_type == PacketType::Heavy {
+                        sprite.color = Color::srgb(1., 0.6, 0.2);
+                    }
+                    commands.spawn((
+                        PlayerPacket,
+                        packet,
+                        sprite,
+                        transform,
+                        Name::from("Player packet"),
+                    ))
+                }
+            }
+            .id()
+        })
+        .collect();
+
+    for saved in &save.projectiles {
+        commands.spawn((
+            Projectile::load(saved, &packets),
+            Sprite::from_image(asset_server.load("projectile.png")),
+            Transform::from_translation(saved.pos),
+        ));
+    }
+
+    health.value = save.health;
+    currency.value = save.currency;
+    *run = save.run;
+    *wave_manager = match save.waves {
+        Some(waves) => WaveManager::resume(waves, get_level(save.level)),
+        None => WaveManager::default(),
+    };
+}
+
+//puts back what the items of a save game went through, once they're spawned
+fn restore_items(
+    mut items: Query<(
+        Entity,
+        &Resumed,
+        Option<&mut PcHealth>,
+        Option<&mut HeldPackets>,
+        Option<&mut ServerOutput>,
+        Option<&mut Sprite>,
+    )>,
+    mut commands: Commands,
+) {
+    for (entity, resumed, pc_health, held, output, sprite) in &mut items {
+        let saved = &resumed.0;
+
+        if let (Some(mut pc_health), Some(h)) = (pc_health, &saved.health) {
+            pc_health.value = h.value;
+            pc_health.since_damage = h.since_damage;
+            pc_health.regenerating = h.regenerating;
+            if h.value <= 0 {
+                commands.entity(entity).insert(Destroyed);
+                if let Some(mut sprite) = sprite {
+                    sprite.color = Color::srgb(0.3, 0.3, 0.3);
+                }
+            }
+        }
+        if let (Some(mut held), Some(h)) = (held, &saved.held) {
+            held.held = h.clone();
+        }
+        if let (Some(mut output), Some(o)) = (output, &saved.output) {
+            output.ports = o.ports;
+            output.packet_type = o.packet_type;
+            output.burst = o.burst;
+        }
+        if saved.level > 0 {
+            commands.entity(entity).insert(RestoreLevel(saved.level));
+        }
+        commands
+            .entity(entity)
+            .remove::<Resumed>()
+            .insert(ResumedTimers(saved.timers.clone()));
+    }
+}
+
+//puts the timers of the items back where they were, once their upgrades are replayed
+fn restore_timers(
+    mut items: Query<
+        (
+            Entity,
+            &ResumedTimers,
+            Option<&mut FireRate>,
+            Option<&mut ServerOutput>,
+            Option<&mut ScanRate>,
+            Option<&mut Regen>,
+        ),
+        Without<RestoreLevel>,
+    >,
+    mut commands: Commands,
+) {
+    for (entity, resumed, fire_rate, output, scan_rate, regen) in &mut items {
+        let saved = &resumed.0;
+        let elapsed = Duration::from_secs_f32;
+
+        if let (Some(mut fire_rate), Some(secs)) = (fire_rate, saved.fire_rate) {
+            fire_rate.0.set_elapsed(elapsed(secs));
+        }
+        if let (Some(mut output), Some((left, secs))) = (output, saved.burst) {
+            output.burst_left = left;
+            output.burst_timer.set_elapsed(elapsed(secs));
+        }
+        if let (Some(mut scan_rate), Some(secs)) = (scan_rate, saved.scan_rate) {
+            scan_rate.0.set_elapsed(elapsed(secs));
+        }
+        if let (Some(mut regen), Some(stored)) = (regen, saved.regen_stored) {
+            regen.stored = stored;
+        }
+        commands.entity(entity).remove::<ResumedTimers>();
+    }
+}
+
+//a level that's over can't be continued
+fn delete_save_game() {
+    if SaveGame::exists() {
+        fs::remove_file(SAVE_GAME_PATH).ok();
+    }
+}
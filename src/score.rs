@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::game::{EditorStates, GameStates};
+
+// points for each health point left
+const HEALTH_POINTS: u32 = 20;
+// points for each credit left
+const CREDIT_POINTS: u32 = 1;
+// points for each enemy packet destroyed
+const PACKET_POINTS: u32 = 10;
+// runs shorter than this earn points for every second they're under it
+const PAR_SECONDS: f32 = 300.;
+const TIME_POINTS: u32 = 5;
+// runs kept on the leaderboard of a level
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// what the level being played went through so far
+#[derive(serde::Serialize, serde::Deserialize, Resource, Clone)]
+pub struct RunStats {
+    pub seconds: f32,
+    pub packets_destroyed: u32,
+    // tells runs of the same level apart on the leaderboard, random and not shown
+    pub run_id: u64,
+    // played since the level was started or continued, what goes in the stats
+    #[serde(skip)]
+    pub session_seconds: f32,
+}
+
+//every run gets an id of its own
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            seconds: 0.,
+            packets_destroyed: 0,
+            run_id: rand::random(),
+            session_seconds: 0.,
+        }
+    }
+}
+
+// the points a run earned, by where they came from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Score {
+    pub health: u32,
+    pub credits: u32,
+    pub time: u32,
+    pub packets: u32,
+}
+
+impl Score {
+    pub fn new(health: i32, currency: i32, run: &RunStats) -> Score {
+        Score {
+            health: health.max(0) as u32 * HEALTH_POINTS,
+            credits: currency.max(0) as u32 * CREDIT_POINTS,
+            time: (PAR_SECONDS - run.seconds).max(0.) as u32 * TIME_POINTS,
+            packets: run.packets_destroyed * PACKET_POINTS,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.health + self.credits + self.time + self.packets
+    }
+}
+
+// a run on the leaderboard of a level
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u32,
+    pub date: String,
+    // leaderboards saved before the rename called it seed
+    #[serde(alias = "seed")]
+    pub run_id: u64,
+}
+
+//puts the run on the leaderboard if it's good enough, returns where it placed from 0
+pub fn place_on_leaderboard(
+    leaderboard: &mut Vec<LeaderboardEntry>,
+    entry: LeaderboardEntry,
+) -> Option<usize> {
+    // ties go to the run that got there first
+    let place = leaderboard
+        .iter()
+        .position(|e| entry.score > e.score)
+        .unwrap_or(leaderboard.len());
+    if place >= LEADERBOARD_SIZE {
+        return None;
+    }
+    leaderboard.insert(place, entry);
+    leaderboard.truncate(LEADERBOARD_SIZE);
+    Some(place)
+}
+
+// the score of the level just beaten, for the victory screen
+#[derive(Resource)]
+pub struct RunScore {
+    pub score: Score,
+    pub seconds: f32,
+    pub packets_destroyed: u32,
+    // where the run placed, None when it didn't make the leaderboard
+    pub place: Option<usize>,
+    // empty for levels without one, like untitled custom levels
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>();
+        app.add_systems(
+            Update,
+            tick_run.run_if(in_state(GameStates::InGame).and(in_state(EditorStates::Playing))),
+        );
+    }
+}
+
+fn tick_run(time: Res<Time>, mut run: ResMut<RunStats>) {
+    run.seconds += time.delta_secs();
+    run.session_seconds += time.delta_secs();
+}
@@ -1,4 +1,7 @@
 use bevy::{math::vec3, prelude::*};
+
+use crate::profiles::Profiles;
+
 const SHAKE_MULT: f32 = 80.;
 
 #[derive(Component)]
@@ -24,9 +27,13 @@ fn shake(
     mut shakable: Query<(Entity, &mut Shake, &mut Transform)>,
     mut commands: Commands,
     time: Res<Time>,
+    profiles: Option<Res<Profiles>>,
 ) {
+    // turned off in the profile settings
+    let enabled = profiles.is_none_or(|p| p.current().settings.screen_shake);
+
     for (entity, mut shake, mut transl) in shakable.iter_mut() {
-        if shake.timer.tick(time.delta()).just_finished() {
+        if shake.timer.tick(time.delta()).just_finished() || !enabled {
             transl.translation = shake.initial_pos;
             commands.entity(entity).remove::<Shake>();
             continue;
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::game::{GameStates, InGame};
+use crate::game::{EditorStates, GameStates, InGame};
+
+// credits at the start of levels that don't set their own
+pub const STARTING_CURRENCY: i32 = 300;
 
 #[derive(Resource)]
 pub struct Currency {
@@ -16,8 +19,13 @@ pub struct UpdateCurrencyEvent(pub i32);
 pub struct CurrencyPlugin;
 impl Plugin for CurrencyPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Currency { value: 300 });
-        app.add_systems(OnEnter(GameStates::InGame), init_currency);
+        app.insert_resource(Currency {
+            value: STARTING_CURRENCY,
+        });
+        app.add_systems(
+            OnEnter(GameStates::InGame),
+            init_currency.run_if(in_state(EditorStates::Playing)),
+        );
         app.add_systems(Update, update_currency.run_if(in_state(GameStates::InGame)));
     }
 }
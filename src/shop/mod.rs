@@ -3,48 +3,85 @@ pub mod shop_items;
 use currency::*;
 
 use bevy::{math::vec2, prelude::*};
-use shop_items::{ItemType, ShopRefID, ShopUI, spawn_shop_item};
+use shop_items::{ItemType, ShopRefID, ShopUI, shop_label, spawn_shop_item};
 
-use crate::items::cables::CableDirection;
+use crate::grid::GridRoot;
+use crate::items::registry::ItemRegistry;
 use crate::shop::shop_items::ShopPosition;
-use crate::{camera::SPRITE_SIZE, game::GameStates};
+use crate::{
+    camera::{SPRITE_SIZE, grid_center},
+    game::{EditorStates, GameStates},
+};
 
 pub struct ShopPlugin;
 
 impl Plugin for ShopPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(CurrencyPlugin);
-        app.add_systems(OnEnter(GameStates::InGame), init_shop_items);
-        app.add_systems(Update, move_shop_ui.run_if(in_state(GameStates::InGame)));
+        app.add_systems(
+            Update,
+            (init_shop_items, move_shop_ui, update_prices).run_if(in_state(GameStates::InGame)),
+        );
         app.add_event::<UpdateCurrencyEvent>();
     }
 }
 
+//lays the shop out under the grid, again whenever a grid of another size is loaded
+//the level editor gets every item for free instead
 pub fn init_shop_items(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    camera: Query<&Transform, With<Camera2d>>,
+    roots: Query<&GridRoot, Added<GridRoot>>,
+    old_items: Query<Entity, Or<(With<ShopPosition>, With<ShopUI>)>>,
+    registry: Res<ItemRegistry>,
+    editor: Res<State<EditorStates>>,
 ) {
-    let Ok(camera_x) = camera.get_single().map(|x| x.translation.x) else {
+    let Some(root) = roots.iter().last() else {
         return;
     };
+    for item in &old_items {
+        commands.entity(item).despawn();
+    }
+    let camera_x = grid_center(root.size).x;
 
-    let shop_items = vec![
-        ItemType::Router,
-        ItemType::Switch,
-        ItemType::Cable(CableDirection::Horizontal),
-        ItemType::Server,
-        // for internal purposes
-        ItemType::PC,
-        ItemType::EnemyPC,
-    ];
+    let editing = *editor == EditorStates::Editing;
+    let shop_items = match editing {
+        true => registry.palette_items(),
+        false => registry.shop_items(),
+    };
 
     const ITEM_SPACE: usize = 50;
     let n = shop_items.len();
 
     for (index, item) in shop_items.iter().enumerate() {
         let x: f32 = camera_x + index as f32 * ITEM_SPACE as f32 - (ITEM_SPACE * n) as f32 / 2.;
-        spawn_shop_item(&mut commands, &asset_server, *item, vec2(x, -30.));
+        spawn_shop_item(
+            &mut commands,
+            &asset_server,
+            &registry,
+            *item,
+            vec2(x, -30.),
+            editing,
+        );
+    }
+}
+
+//shop labels follow price changes from the balance file
+fn update_prices(
+    shop_items: Query<(&ItemType, &ShopRefID)>,
+    mut labels: Query<&mut Text, With<ShopUI>>,
+    registry: Res<ItemRegistry>,
+    editor: Res<State<EditorStates>>,
+) {
+    if !registry.is_changed() {
+        return;
+    }
+
+    for (item_type, ui_id) in &shop_items {
+        let Ok(mut label) = labels.get_mut(ui_id.0) else {
+            continue;
+        };
+        label.0 = shop_label(registry.get(*item_type), *editor == EditorStates::Editing);
     }
 }
 
@@ -2,75 +2,24 @@ use bevy::prelude::*;
 
 use crate::{
     game::InGame,
-    items::{
-        cables::{Cable, CableDirection},
-        enemy_pc::EnemyPC,
-        pcs::PC,
-        routers::Router,
-        servers::Server,
-        switches::Switch,
-    },
+    items::registry::{ItemInfo, ItemRegistry},
 };
 
-#[derive(Component, Clone, Copy)]
+// what an item is, everything else about it lives in the ItemRegistry
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ItemType {
     PC,
+    CriticalPC,
     EnemyPC,
     Router,
     Switch,
-    Cable(CableDirection),
+    Cable,
     Server,
-}
-
-impl ItemType {
-    //assets
-    pub fn sprite_path(&self) -> String {
-        match self {
-            ItemType::PC => "pc.png",
-            ItemType::EnemyPC => "enemy_pc.png",
-            ItemType::Router => "router.png",
-            ItemType::Switch => "switch.png",
-            ItemType::Cable(_) => "cable.png",
-            ItemType::Server => "server.png",
-        }
-        .to_string()
-    }
-
-    //prices
-    pub(crate) fn price(&self) -> u32 {
-        match self {
-            ItemType::Router => 20,
-            ItemType::Switch => 25,
-            ItemType::Cable(_) => 1, //1 credit per square
-            ItemType::Server => 30,
-
-            _ => 0,
-        }
-    }
-
-    //names
-    pub fn name(&self) -> String {
-        match self {
-            ItemType::PC => "PC",
-            ItemType::EnemyPC => "Enemy PC",
-            ItemType::Router => "Router",
-            ItemType::Switch => "Switch",
-            ItemType::Cable(_) => "Cable",
-            ItemType::Server => "Server",
-        }
-        .to_string()
-    }
-
-    pub fn add_component(&self, entity_commands: &mut EntityCommands) {
-        match self {
-            Self::PC => entity_commands.insert(PC),
-            Self::EnemyPC => entity_commands.insert(EnemyPC),
-            Self::Router => entity_commands.insert(Router),
-            Self::Switch => entity_commands.insert(Switch),
-            Self::Cable(dir) => entity_commands.insert(Cable { dir: *dir }),
-            Self::Server => entity_commands.insert(Server),
-        };
-    }
+    Honeypot,
+    Antivirus,
+    Ups,
+    DataCenter,
+    Rack,
 }
 
 #[derive(Component)]
@@ -88,13 +37,16 @@ pub struct ShopPosition(pub(crate) Vec2);
 pub fn spawn_shop_item(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
+    registry: &ItemRegistry,
     item_type: ItemType,
     pos: Vec2,
+    free: bool,
 ) -> Entity {
+    let info = registry.get(item_type);
     let ui_id = commands
         .spawn((
             ShopUI,
-            Text::new(format!("{}, ${}", item_type.name(), item_type.price())),
+            Text::new(shop_label(info, free)),
             TextFont {
                 font_size: 14.0,
                 ..Default::default()
@@ -107,9 +59,19 @@ pub fn spawn_shop_item(
             ShopPosition(pos),
             item_type,
             ShopRefID(ui_id),
-            Sprite::from_image(asset_server.load(item_type.sprite_path())),
-            Transform::from_translation(pos.extend(0.)),
-            Name::new(item_type.name()),
+            Sprite::from_image(asset_server.load(info.sprite)),
+            //items bigger than a cell are shown shrunk down in the shop
+            Transform::from_translation(pos.extend(0.))
+                .with_scale(Vec3::splat(1. / info.footprint.max_element() as f32)),
+            Name::new(info.name),
         ))
         .id()
 }
+
+//name and price under a shop item, just the name when it's free
+pub fn shop_label(info: &ItemInfo, free: bool) -> String {
+    match free {
+        true => info.name.to_string(),
+        false => format!("{}, ${}", info.name, info.price),
+    }
+}
@@ -25,3 +25,474 @@ fn set_health(mut health: ResMut<Health>, health_test: Res<HealthTest>) {
 fn is_health(health: Res<Health>, health_test: Res<HealthTest>) {
     assert_eq!(health.value, health_test.value);
 }
+
+#[test]
+fn test_grid_item_format() {
+    use crate::grid::save_load::GridItem;
+    use crate::items::cables::CableDirection;
+
+    let json = r#"[{"PC":[8,4]},{"Cable":[{"min":[9,4],"max":[21,4]},"Horizontal"]}]"#;
+    let items: Vec<GridItem> = serde_json::from_str(json).unwrap();
+
+    assert_eq!(items[0], GridItem::Item("PC".to_string(), UVec2::new(8, 4)));
+    assert_eq!(
+        items[1],
+        GridItem::Cable(URect::new(9, 4, 21, 4), CableDirection::Horizontal)
+    );
+    assert_eq!(serde_json::to_string(&items).unwrap(), json);
+}
+
+#[test]
+fn test_balance_fallback() {
+    use crate::balance::Balance;
+    use crate::items::packets::PacketType;
+
+    let balance: Balance =
+        serde_json::from_str(r#"{"packets":{"Heavy":{"speed":9}},"upgrades":{"Ups":{}}}"#).unwrap();
+
+    let heavy = balance.packet_stats(PacketType::Heavy);
+    assert_eq!(heavy.speed, 9.);
+    assert_eq!(heavy.health, 20);
+    assert_eq!(balance.upgrades("Ups").value(0, "rate", 2.), 2.);
+    assert_eq!(balance.upgrades("Server").next_price(2, None), None);
+
+    // the shipped file has to stay readable
+    let _: Balance = serde_json::from_str(include_str!("../assets/balance.json")).unwrap();
+}
+
+// cargo test --release bench_cabled_grid -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_cabled_grid() {
+    use crate::grid::{DEFAULT_GRID_SIZE, Grid};
+    use std::time::Instant;
+
+    for size in [DEFAULT_GRID_SIZE, DEFAULT_GRID_SIZE * 10] {
+        //every row is one cable as wide as the board
+        let mut grid = Grid::new(size);
+        let cables: Vec<Entity> = (0..size.y).map(Entity::from_raw).collect();
+        for (y, cable) in cables.iter().enumerate() {
+            grid.occupy((0..size.x).map(|x| UVec2::new(x, y as u32)), *cable);
+        }
+
+        let start = Instant::now();
+        let covered: usize = cables.iter().map(|c| grid.cells_of(*c).len()).sum();
+        let lookup = start.elapsed();
+        assert_eq!(covered, (size.x * size.y) as usize);
+
+        let start = Instant::now();
+        let saved = grid.entities().count();
+        let listing = start.elapsed();
+        assert_eq!(saved, cables.len());
+
+        let start = Instant::now();
+        for cable in &cables {
+            grid.clear_entity(*cable);
+        }
+        let clearing = start.elapsed();
+        assert!(grid.entities().next().is_none());
+
+        println!(
+            "{}x{} board: cells {lookup:?}, listing {listing:?}, clearing {clearing:?}",
+            size.x, size.y
+        );
+    }
+}
+
+#[test]
+fn test_topology() {
+    use crate::grid::Grid;
+    use crate::grid::topology::{NodeKind, Topology, update_topology};
+    use crate::items::cables::{Cable, CableDirection};
+    use crate::shop::shop_items::ItemType;
+
+    let mut app = App::new();
+    app.init_resource::<Grid>();
+    app.init_resource::<Topology>();
+    app.add_systems(Update, update_topology);
+
+    //enemy at (0,0) -> router at (4,0) -> PC at (8,0)
+    //and a longer way around through a switch at (0,4) and a router at (8,4)
+    let world = app.world_mut();
+    let mut item = |item_type: ItemType, cell: UVec2| {
+        let entity = world.spawn(item_type).id();
+        world.resource_mut::<Grid>().place(cell, UVec2::ONE, entity);
+        entity
+    };
+    let enemy = item(ItemType::EnemyPC, UVec2::new(0, 0));
+    let router = item(ItemType::Router, UVec2::new(4, 0));
+    let pc = item(ItemType::PC, UVec2::new(8, 0));
+    let switch = item(ItemType::Switch, UVec2::new(0, 4));
+    item(ItemType::Router, UVec2::new(8, 4));
+    let lonely = item(ItemType::PC, UVec2::new(20, 10));
+
+    let mut cable = |rect: URect, dir: CableDirection| {
+        let entity = world.spawn((ItemType::Cable, Cable { dir, rect })).id();
+        let cells: Vec<UVec2> = (rect.min.x..=rect.max.x)
+            .flat_map(|x| (rect.min.y..=rect.max.y).map(move |y| UVec2::new(x, y)))
+            .collect();
+        world.resource_mut::<Grid>().occupy(cells, entity);
+        entity
+    };
+    let short = cable(URect::new(1, 0, 3, 0), CableDirection::Horizontal);
+    cable(URect::new(5, 0, 7, 0), CableDirection::Horizontal);
+    cable(URect::new(0, 1, 0, 3), CableDirection::Vertical);
+    cable(URect::new(1, 4, 7, 4), CableDirection::Horizontal);
+    cable(URect::new(8, 1, 8, 3), CableDirection::Vertical);
+    // nothing on its far end, so it isn't a link
+    cable(URect::new(9, 0, 12, 0), CableDirection::Horizontal);
+
+    app.update();
+    let topology = app.world().resource::<Topology>();
+
+    assert_eq!(topology.node(switch), Some(NodeKind::Switch));
+    assert_eq!(topology.links().len(), 5);
+
+    let path = topology.shortest_path(enemy, pc).unwrap();
+    assert_eq!(path.nodes, vec![enemy, router, pc]);
+    assert_eq!(path.cables[0], short);
+    assert_eq!(path.length, 6);
+
+    assert!(topology.reachable(enemy).contains(&pc));
+    assert!(!topology.reachable(enemy).contains(&lonely));
+    assert!(topology.shortest_path(enemy, lonely).is_none());
+
+    let mut paths = topology.enemy_paths();
+    paths.sort_by_key(|p| p.length);
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1].nodes[1], switch);
+    assert_eq!(paths[1].length, 3 + 7 + 3);
+}
+
+#[test]
+fn test_parse_waves() {
+    use crate::editor::parse_waves;
+    use crate::items::packets::PacketType;
+
+    let waves = parse_waves("Basic 1, mid 0.5; Heavy 2").unwrap();
+    assert_eq!(waves.len(), 2);
+    assert_eq!(
+        waves[0].packets(),
+        &[(PacketType::Basic, 1.), (PacketType::Mid, 0.5)]
+    );
+
+    assert!(parse_waves("Basic").is_err());
+    assert!(parse_waves("Tiny 1").is_err());
+    assert!(parse_waves("Basic 1;").is_err());
+}
+
+#[test]
+fn test_grid_migrations() {
+    use crate::encoding::Format;
+    use crate::grid::format::{GRID_VERSION, GridError};
+    use crate::grid::save_load::GridState;
+    use crate::levels::{DestroyedPcRule, LossRule};
+
+    let old = r#"{"name":"first","items":[{"PC":[8,4]}]}"#;
+    let state = GridState::parse(old.as_bytes(), Format::Json).unwrap();
+    assert_eq!(state.meta.title, "first");
+    assert_eq!(state.loss_rule, LossRule::AnyPc);
+
+    let hard = include_bytes!("../assets/grids/hard.grid.json");
+    let hard = GridState::parse(hard, Format::Json).unwrap();
+    assert_eq!(hard.loss_rule, LossRule::AllPcs);
+    assert_eq!(hard.destroyed_pc, DestroyedPcRule::Continue);
+
+    let written = serde_json::to_value(&state).unwrap();
+    assert_eq!(written["version"], GRID_VERSION);
+    let again = GridState::parse(written.to_string().as_bytes(), Format::Json).unwrap();
+    assert_eq!(again.meta, state.meta);
+
+    let newer = format!(r#"{{"version":{},"items":[]}}"#, GRID_VERSION + 1);
+    assert!(matches!(
+        GridState::parse(newer.as_bytes(), Format::Json),
+        Err(GridError::Newer(_))
+    ));
+}
+
+#[test]
+fn test_binary_grid() {
+    use crate::encoding::{DecodeError, Format, encode_binary};
+    use crate::grid::format::{GRID_VERSION, GridError};
+    use crate::grid::save_load::GridState;
+
+    let json = r#"{"version":1,"meta":{"title":"bin"},"items":[{"PC":[8,4]},{"Cable":[{"min":[9,4],"max":[21,4]},"Horizontal"]}]}"#;
+    let state = GridState::parse(json.as_bytes(), Format::Json).unwrap();
+
+    let mut bytes = encode_binary(&state, GRID_VERSION);
+    let back = GridState::parse(&bytes, Format::Binary).unwrap();
+    assert_eq!(
+        serde_json::to_value(&back).unwrap(),
+        serde_json::to_value(&state).unwrap()
+    );
+
+    *bytes.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        GridState::parse(&bytes, Format::Binary),
+        Err(GridError::Binary(DecodeError::Checksum))
+    ));
+}
+
+#[test]
+fn test_level_codes() {
+    use crate::encoding::Format;
+    use crate::grid::level_code::{CodeError, export_code, import_code};
+    use crate::grid::save_load::GridState;
+
+    let json = r#"{"version":1,"meta":{"title":"shared"},"items":[{"PC":[8,4]},{"EnemyPC":[22,4]}],"money":500}"#;
+    let state = GridState::parse(json.as_bytes(), Format::Json).unwrap();
+    let code = export_code(&state);
+
+    let back = import_code(&format!(" {}\n", code)).unwrap();
+    assert_eq!(back.meta.title, "shared");
+    assert_eq!(back.money, Some(500));
+
+    assert!(matches!(import_code("hello"), Err(CodeError::NotACode)));
+    assert!(matches!(
+        import_code(&format!("{code}!")),
+        Err(CodeError::BadCharacters)
+    ));
+    //a changed character breaks either the compression or the checksum
+    let mut tampered = code.clone().into_bytes();
+    let last = tampered.len() - 3;
+    tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+    assert!(import_code(&String::from_utf8(tampered).unwrap()).is_err());
+}
+
+#[test]
+fn test_game_levels_from_bytes() {
+    use crate::game::GameLevels;
+
+    assert_eq!(GameLevels::try_from(2), Ok(GameLevels::Medium));
+    assert!(GameLevels::try_from(200).is_err());
+    assert!(serde_json::from_str::<GameLevels>("200").is_err());
+}
+
+#[test]
+fn test_profiles() {
+    use crate::game::GameLevels;
+    use crate::profiles::{LevelResult, Profile, ProfileError};
+
+    let mut profile = Profile::parse(br#"{"version":1,"name":"ada","highest":3}"#).unwrap();
+    assert_eq!(profile.highest, GameLevels::Hard);
+    assert!(profile.settings.screen_shake);
+
+    let result = |score, seconds| LevelResult {
+        health: 0,
+        currency: 0,
+        seconds,
+        score,
+    };
+    profile.record("Easy".to_string(), result(500, 90.));
+    profile.record("Easy".to_string(), result(400, 30.));
+    profile.record("Easy".to_string(), result(500, 60.));
+    assert_eq!(profile.best["Easy"], result(500, 60.));
+
+    assert!(matches!(
+        Profile::parse(br#"{"version":99,"name":"ada","highest":3,"new":[]}"#),
+        Err(ProfileError::Newer(99))
+    ));
+}
+
+#[test]
+fn test_leaderboard() {
+    use crate::score::{LEADERBOARD_SIZE, LeaderboardEntry, place_on_leaderboard};
+
+    let entry = |score| LeaderboardEntry {
+        name: "ada".to_string(),
+        score,
+        date: "2025-01-01".to_string(),
+        run_id: 0,
+    };
+    let mut leaderboard: Vec<LeaderboardEntry> = (1..=LEADERBOARD_SIZE as u32)
+        .rev()
+        .map(|score| entry(score * 100))
+        .collect();
+
+    assert_eq!(place_on_leaderboard(&mut leaderboard, entry(50)), None);
+    //ties go after the runs already there
+    assert_eq!(place_on_leaderboard(&mut leaderboard, entry(900)), Some(2));
+    assert_eq!(leaderboard.len(), LEADERBOARD_SIZE);
+    assert_eq!(leaderboard.last().unwrap().score, 200);
+    assert_eq!(place_on_leaderboard(&mut Vec::new(), entry(0)), Some(0));
+}
+
+#[test]
+fn test_undo_redo() {
+    use bevy::{ecs::system::RunSystemOnce, state::app::StatesPlugin};
+
+    use crate::game::{EditorStates, GameLevels, GameStates};
+    use crate::grid::{Grid, save_load::spawn_item};
+    use crate::history::{Action, History, HistoryPlugin, Snapshot};
+    use crate::items::registry::{ItemInfo, ItemRegistry, RegisterItem};
+    use crate::items::upgrades::{RestoreLevel, UpgradeLevel};
+    use crate::levels::{WaveManager, get_level};
+    use crate::shop::{currency::Currency, shop_items::ItemType};
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin));
+    app.init_asset::<Image>();
+    app.insert_state(GameStates::InGame);
+    app.insert_state(EditorStates::Playing);
+    app.insert_resource(WaveManager::default());
+    app.insert_resource(Grid::new(UVec2::new(10, 10)));
+    app.insert_resource(Currency { value: 100 });
+    app.init_resource::<ButtonInput<KeyCode>>();
+    app.register_item(
+        ItemType::Server,
+        ItemInfo {
+            name: "Server",
+            sprite: "server.png",
+            price: 30,
+            footprint: UVec2::ONE,
+            key: "Server",
+            connectable: true,
+            shop_slot: None,
+            spawn: |entity| {
+                entity.insert(UpgradeLevel::from(20));
+            },
+        },
+    );
+    app.add_plugins(HistoryPlugin);
+    app.update();
+
+    let cell = UVec2::new(2, 3);
+    let item = Snapshot {
+        item_type: ItemType::Server,
+        anchor: cell,
+        cable: None,
+        level: 0,
+    };
+    //Ctrl and the key pressed this frame, as the input plugin would
+    let press = |app: &mut App, key: KeyCode| {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.reset_all();
+        keys.press(KeyCode::ControlLeft);
+        keys.press(key);
+        app.update();
+    };
+    let currency = |app: &App| app.world().resource::<Currency>().value;
+    let at = |app: &App| app.world().resource::<Grid>().at(cell);
+
+    //placing
+    let entity = app
+        .world_mut()
+        .run_system_once(
+            |mut grid: ResMut<Grid>,
+             registry: Res<ItemRegistry>,
+             asset_server: Res<AssetServer>,
+             mut commands: Commands| {
+                spawn_item(
+                    UVec2::new(2, 3),
+                    ItemType::Server,
+                    &registry,
+                    &mut grid,
+                    &asset_server,
+                    &mut commands,
+                )
+            },
+        )
+        .unwrap();
+    app.world_mut().resource_mut::<Currency>().value -= 30;
+    app.world_mut()
+        .resource_mut::<History>()
+        .record(Action::Place {
+            entity,
+            item: item.clone(),
+            price: 30,
+        });
+
+    press(&mut app, KeyCode::KeyZ);
+    assert_eq!(currency(&app), 100);
+    assert_eq!(at(&app), None);
+    press(&mut app, KeyCode::KeyY);
+    assert_eq!(currency(&app), 70);
+    let entity = at(&app).expect("the item is placed again");
+
+    //upgrading keeps the same item on the grid
+    app.world_mut().resource_mut::<Currency>().value -= 20;
+    app.world_mut()
+        .resource_mut::<History>()
+        .record(Action::Upgrade {
+            entity,
+            from: 0,
+            price: 20,
+        });
+
+    press(&mut app, KeyCode::KeyZ);
+    assert_eq!(currency(&app), 70);
+    assert_eq!(at(&app), Some(entity));
+    assert_eq!(
+        app.world().get::<RestoreLevel>(entity).map(|r| r.0),
+        Some(0)
+    );
+    press(&mut app, KeyCode::KeyY);
+    assert_eq!(currency(&app), 50);
+    assert_eq!(at(&app), Some(entity));
+    assert_eq!(
+        app.world().get::<RestoreLevel>(entity).map(|r| r.0),
+        Some(1)
+    );
+
+    //removing
+    app.world_mut().resource_mut::<Grid>().clear_entity(entity);
+    app.world_mut().despawn(entity);
+    app.world_mut().resource_mut::<Currency>().value += 15;
+    app.world_mut()
+        .resource_mut::<History>()
+        .record(Action::Remove {
+            entity,
+            item,
+            refund: 15,
+        });
+
+    press(&mut app, KeyCode::KeyZ);
+    assert_eq!(currency(&app), 50);
+    assert!(at(&app).is_some());
+    press(&mut app, KeyCode::KeyY);
+    assert_eq!(currency(&app), 65);
+    assert_eq!(at(&app), None);
+
+    //once a wave is sent, nothing before it can be taken back
+    app.insert_resource(WaveManager::new(get_level(GameLevels::Easy)));
+    press(&mut app, KeyCode::KeyZ);
+    assert_eq!(currency(&app), 65);
+    assert_eq!(at(&app), None);
+}
+
+#[test]
+fn test_grid_validation() {
+    use crate::encoding::Format;
+    use crate::grid::save_load::GridState;
+    use crate::items::registry::{ItemInfo, ItemRegistry, RegisterItem};
+    use crate::shop::shop_items::ItemType;
+
+    let mut app = App::new();
+    app.register_item(
+        ItemType::DataCenter,
+        ItemInfo {
+            name: "Data Center",
+            sprite: "data_center.png",
+            price: 0,
+            footprint: UVec2::splat(2),
+            key: "DataCenter",
+            connectable: true,
+            shop_slot: None,
+            spawn: |_| {},
+        },
+    );
+    let registry = app.world().resource::<ItemRegistry>();
+    let validate = |items: &str| {
+        let json = format!(r#"{{"version":1,"width":10,"height":10,"items":{items}}}"#);
+        GridState::parse(json.as_bytes(), Format::Json)
+            .unwrap()
+            .validate(registry)
+    };
+
+    //a position near the end of u32 doesn't overflow with the footprint
+    let far = validate(&format!(r#"[{{"DataCenter":[{},3]}}]"#, u32::MAX)).unwrap_err();
+    assert!(far.contains("outside of the grid"), "{far}");
+    let backwards =
+        validate(r#"[{"Cable":[{"min":[5,4],"max":[2,4]},"Horizontal"]}]"#).unwrap_err();
+    assert!(backwards.contains("backwards"), "{backwards}");
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+// seconds a toast stays on screen
+const TOAST_SECONDS: f32 = 6.;
+const TOAST_BACKGROUND: Color = Color::srgba(0.35, 0.05, 0.05, 0.9);
+
+// something that went wrong, told to the player on top of whatever screen they're on
+#[derive(Event)]
+pub struct Toast(pub String);
+
+// column the toasts stack in, kept across every state
+#[derive(Component)]
+struct ToastList;
+
+#[derive(Component)]
+struct ToastTimer(Timer);
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Toast>();
+        app.add_systems(Startup, spawn_toast_list);
+        app.add_systems(Update, (show_toasts, expire_toasts));
+    }
+}
+
+fn spawn_toast_list(mut commands: Commands) {
+    commands.spawn((
+        Name::new("toasts"),
+        ToastList,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        GlobalZIndex(10),
+    ));
+}
+
+fn show_toasts(
+    mut events: EventReader<Toast>,
+    list: Query<Entity, With<ToastList>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(list) = list.get_single() else {
+        return;
+    };
+
+    for ev in events.read() {
+        error!("{}", ev.0);
+        commands.entity(list).with_children(|parent| {
+            parent
+                .spawn((
+                    ToastTimer(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+                    Node {
+                        max_width: Val::Px(700.0),
+                        padding: UiRect::axes(Val::Px(14.0), Val::Px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(TOAST_BACKGROUND),
+                ))
+                .with_child((
+                    Text::new(ev.0.clone()),
+                    TextFont {
+                        font: asset_server.load("fonts/courbd.ttf"),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.95, 0.9, 0.9)),
+                ));
+        });
+    }
+}
+
+fn expire_toasts(
+    time: Res<Time>,
+    mut toasts: Query<(Entity, &mut ToastTimer)>,
+    mut commands: Commands,
+) {
+    for (entity, mut timer) in &mut toasts {
+        if timer.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+// folder under the platform's data directory everything of the player goes in
+const APP_DIR: &str = "PacketTerror";
+// levels made by the player or copied in by them, next to the campaign
+pub const USER_LEVELS_DIR: &str = "levels";
+// level codes exported from the editor
+pub const LEVEL_CODES_DIR: &str = "codes";
+// one file per player profile
+pub const PROFILES_DIR: &str = "profiles";
+
+//where the game keeps what belongs to the player, the working directory when there's no home
+pub fn user_data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.unwrap_or_default().join(APP_DIR)
+}
+
+pub fn user_levels_dir() -> PathBuf {
+    user_data_dir().join(USER_LEVELS_DIR)
+}
+
+pub fn level_codes_dir() -> PathBuf {
+    user_data_dir().join(LEVEL_CODES_DIR)
+}
+
+pub fn profiles_dir() -> PathBuf {
+    user_data_dir().join(PROFILES_DIR)
+}
+
+//a file path in `dir` nothing is using yet, from a name the player typed
+pub fn new_file_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    let stem: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect();
+
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem}_{n}.{extension}"));
+        n += 1;
+    }
+    path
+}
@@ -1,13 +1,17 @@
 use bevy::color::palettes::basic::*;
 use bevy::prelude::*;
 
-use crate::game::GameStates;
+use crate::{
+    game::GameStates,
+    score::{LEADERBOARD_SIZE, RunScore},
+};
 
 use super::VictoryScreen;
 
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
+const RUN_ROW: Color = Color::srgb(0.0, 1.0, 0.0);
 
 #[derive(Event)]
 pub struct WinBtnPress;
@@ -68,8 +72,82 @@ fn button_system(
     }
 }
 
+//where the points came from
+fn breakdown(run: &RunScore) -> String {
+    let seconds = run.seconds as u32;
+    format!(
+        "health +{}  credits +{}  time +{} ({}:{:02})  packets +{} ({} destroyed)",
+        run.score.health,
+        run.score.credits,
+        run.score.time,
+        seconds / 60,
+        seconds % 60,
+        run.score.packets,
+        run.packets_destroyed,
+    )
+}
+
+//the score of the run and the leaderboard of the level, with the run in it
+fn spawn_score(parent: &mut ChildBuilder, run: &RunScore, asset_server: &AssetServer) {
+    let font = |size: f32| TextFont {
+        font: asset_server.load("fonts/courbd.ttf"),
+        font_size: size,
+        ..default()
+    };
+
+    parent.spawn((
+        Text::new(format!("SCORE {}", run.score.total())),
+        font(26.0),
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+    ));
+    parent.spawn((
+        Text::new(breakdown(run)),
+        font(14.0),
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+    ));
+    // levels without a leaderboard only get the score
+    if run.leaderboard.is_empty() {
+        return;
+    }
+
+    let placed = match run.place {
+        Some(place) => format!("#{} on this level's leaderboard", place + 1),
+        None => format!("not in this level's top {LEADERBOARD_SIZE}"),
+    };
+    parent.spawn((
+        Text::new(placed),
+        font(20.0),
+        TextColor(Color::srgb(0.5, 0.0, 0.0)),
+    ));
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|list| {
+            for (i, entry) in run.leaderboard.iter().enumerate() {
+                let color = match run.place == Some(i) {
+                    true => RUN_ROW,
+                    false => Color::srgb(0.8, 0.8, 0.8),
+                };
+                list.spawn((
+                    Text::new(format!(
+                        "{:>2}. {:>6}  {:<12} {}",
+                        i + 1,
+                        entry.score,
+                        entry.name,
+                        entry.date
+                    )),
+                    font(14.0),
+                    TextColor(color),
+                ));
+            }
+        });
+}
+
 //creates the UI
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, run: Option<Res<RunScore>>) {
     commands
         .spawn((
             Name::new("victory screen"),
@@ -94,6 +172,9 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 },
                 TextColor(Color::srgb(0.0, 1.0, 0.0)),
             ));
+            if let Some(run) = &run {
+                spawn_score(parent, run, &asset_server);
+            }
             parent
                 .spawn((
                     Button,